@@ -0,0 +1,113 @@
+//! Spatial index queries against scanning every entity.
+
+use bevy::prelude::*;
+use bevy_game_jam_6::game::spatial::SpatialIndex;
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const RADIUS: f32 = 40.0;
+const K: usize = 8;
+/// Side length of the square the entities are scattered over, about a level.
+const EXTENT: f32 = 2000.0;
+
+fn scatter(count: usize) -> Vec<(Entity, Vec3)> {
+    let mut rng = StdRng::seed_from_u64(count as u64);
+    (0..count)
+        .map(|ix| {
+            let position = Vec3::new(
+                rng.random_range(-EXTENT / 2.0..EXTENT / 2.0),
+                rng.random_range(0.0..50.0),
+                rng.random_range(-EXTENT / 2.0..EXTENT / 2.0),
+            );
+            (Entity::from_raw(ix as u32), position)
+        })
+        .collect()
+}
+
+fn index(points: &[(Entity, Vec3)]) -> SpatialIndex {
+    let mut index = SpatialIndex::default();
+    for &(entity, position) in points {
+        index.insert(entity, position);
+    }
+    index
+}
+
+fn brute_force_radius(points: &[(Entity, Vec3)], center: Vec3) -> usize {
+    points
+        .iter()
+        .filter(|(_, position)| position.distance_squared(center) <= RADIUS * RADIUS)
+        .count()
+}
+
+fn brute_force_k_nearest(points: &[(Entity, Vec3)], center: Vec3) -> Vec<(Entity, Vec3)> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|(_, a), (_, b)| {
+        a.distance_squared(center)
+            .total_cmp(&b.distance_squared(center))
+    });
+    sorted.truncate(K);
+    sorted
+}
+
+fn queries(c: &mut Criterion) {
+    for count in [100, 1_000, 10_000] {
+        let points = scatter(count);
+        let index = index(&points);
+        let centers = scatter(64)
+            .into_iter()
+            .map(|(_, center)| center)
+            .collect::<Vec<_>>();
+
+        let mut group = c.benchmark_group(format!("radius/{count}"));
+        group.bench_function(BenchmarkId::new("brute_force", count), |b| {
+            b.iter(|| {
+                for &center in &centers {
+                    black_box(brute_force_radius(&points, center));
+                }
+            })
+        });
+        group.bench_function(BenchmarkId::new("spatial_index", count), |b| {
+            b.iter(|| {
+                for &center in &centers {
+                    black_box(index.within_radius(center, RADIUS).count());
+                }
+            })
+        });
+        group.finish();
+
+        let mut group = c.benchmark_group(format!("k_nearest/{count}"));
+        group.bench_function(BenchmarkId::new("brute_force", count), |b| {
+            b.iter(|| {
+                for &center in &centers {
+                    black_box(brute_force_k_nearest(&points, center));
+                }
+            })
+        });
+        group.bench_function(BenchmarkId::new("spatial_index", count), |b| {
+            b.iter(|| {
+                for &center in &centers {
+                    black_box(index.k_nearest(center, K, |_| true));
+                }
+            })
+        });
+        group.finish();
+    }
+}
+
+fn updates(c: &mut Criterion) {
+    let points = scatter(1_000);
+    let mut index = index(&points);
+    let mut step = 0.0;
+    c.bench_function("update/1000", |b| {
+        b.iter(|| {
+            step += 1.0;
+            for &(entity, position) in &points {
+                index.insert(entity, position + Vec3::X * (step % 100.0));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, queries, updates);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+//! Per-system timings for large waves, run with `cargo bench --features bench --bench waves`.
+
+use bevy_game_jam_6::game::bench::{self, SystemBench};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::time::{Duration, Instant};
+
+/// Times only [`SystemBench::run`], preparing the world in between is free.
+fn bench_system(
+    c: &mut Criterion,
+    name: &str,
+    sizes: &[usize],
+    build: impl Fn(usize) -> SystemBench,
+) {
+    let mut group = c.benchmark_group(name);
+    for &size in sizes {
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            let mut system = build(size);
+            b.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    system.prepare();
+                    let start = Instant::now();
+                    system.run();
+                    total += start.elapsed();
+                }
+                total
+            });
+        });
+    }
+    group.finish();
+}
+
+fn target_ent_sys(c: &mut Criterion) {
+    bench_system(
+        c,
+        "target_ent_sys",
+        &[100, 1_000, 10_000],
+        bench::target_ent,
+    );
+}
+
+fn apply_distance_cost(c: &mut Criterion) {
+    bench_system(
+        c,
+        "apply_distance_cost",
+        &[100, 1_000, 10_000],
+        bench::distance_cost,
+    );
+}
+
+fn animate_in_range(c: &mut Criterion) {
+    // Each ball reaches out to a handful of conduits.
+    bench_system(c, "animate_in_range", &[1, 10, 50], |balls| {
+        bench::lightning_balls(balls, 8)
+    });
+}
+
+criterion_group!(
+    benches,
+    target_ent_sys,
+    apply_distance_cost,
+    animate_in_range
+);
+criterion_main!(benches);
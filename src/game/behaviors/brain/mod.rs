@@ -0,0 +1,174 @@
+//! Behavior tree driven enemy decisions.
+//!
+//! A [`Brain`] ticks its [`BehaviorTree`] and writes the outcome to [`Intent`], the movement and
+//! attack behaviors act on that.
+
+pub mod tree;
+
+use super::ranged_attack::RangedAttack;
+use super::target_ent::TargetEnt;
+use crate::game::asset_tracking::LoadResource;
+use crate::game::health::{
+    DamageEvent, DamageType, Health, HealthSystems, MaxHealth, health_owner,
+};
+use crate::game::pause_controller::PausableSystems;
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::prefabs::enemy_pool::InPlay;
+use crate::game::spark::ZappedBy;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use tree::{BehaviorTree, BehaviorTreeLoader, Senses};
+
+/// What the enemy is doing, set by the running [`tree::Action`].
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum Intent {
+    #[default]
+    Idle,
+    MoveTo,
+    Attack,
+    Flee,
+}
+
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Intent)]
+pub struct Brain {
+    pub tree: Handle<BehaviorTree>,
+    /// Path of the leaf that is running.
+    running: Option<Vec<usize>>,
+    /// How long the running leaf has been running.
+    elapsed: f32,
+    /// The running leaf and its parents, for debugging.
+    pub active_node: String,
+}
+
+impl Brain {
+    pub fn new(tree: Handle<BehaviorTree>) -> Self {
+        Self {
+            tree,
+            running: None,
+            elapsed: 0.0,
+            active_node: String::new(),
+        }
+    }
+
+    /// Forgets what was running, the tree starts over on the next tick.
+    pub fn reset(&mut self) {
+        self.running = None;
+        self.elapsed = 0.0;
+        self.active_node.clear();
+    }
+}
+
+#[auto_register_type]
+#[derive(Resource, Asset, Debug, Clone, Reflect)]
+pub struct BrainAssets {
+    #[dependency]
+    pub melee: Handle<BehaviorTree>,
+    #[dependency]
+    pub ranged: Handle<BehaviorTree>,
+}
+
+impl FromWorld for BrainAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            melee: assets.load("ai/melee.bt.ron"),
+            ranged: assets.load("ai/ranged.bt.ron"),
+        }
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.init_asset::<BehaviorTree>();
+    app.init_asset_loader::<BehaviorTreeLoader>();
+    app.load_resource::<BrainAssets>();
+    app.add_systems(
+        FixedUpdate,
+        (think, melee_attack)
+            .chain()
+            .in_set(PausableSystems)
+            .before(HealthSystems),
+    );
+}
+
+fn think(
+    time: Res<Time>,
+    trees: Res<Assets<BehaviorTree>>,
+    mut brains: Query<
+        (
+            &mut Brain,
+            &mut Intent,
+            &GlobalTransform,
+            Option<&TargetEnt>,
+            Option<(&Health, &MaxHealth)>,
+            Has<ZappedBy>,
+        ),
+        InPlay,
+    >,
+    transforms: Query<&GlobalTransform>,
+) {
+    let dt = time.delta_secs();
+    for (mut brain, mut intent, transform, target, health, zapped) in brains.iter_mut() {
+        let Some(tree) = trees.get(&brain.tree) else {
+            continue;
+        };
+        let target_distance = target
+            .and_then(|target| transforms.get(target.target_ent).ok())
+            .map(|target| {
+                target
+                    .translation()
+                    .xz()
+                    .distance(transform.translation().xz())
+            });
+        let senses = Senses {
+            target_distance,
+            attack_distance: target.map_or(0.0, |target| target.within_distance),
+            health_fraction: health.map_or(1.0, |(health, max)| health.0 / max.0.max(f32::EPSILON)),
+            zapped,
+        };
+        let (_, leaf) = tree.tick(&senses, brain.running.as_deref(), brain.elapsed + dt);
+
+        let path = leaf.as_ref().map(|leaf| leaf.path.clone());
+        if path == brain.running {
+            brain.elapsed += dt;
+        } else {
+            brain.elapsed = 0.0;
+            brain.active_node = path
+                .as_deref()
+                .map_or_else(String::new, |path| tree.describe(path));
+            brain.running = path;
+        }
+        intent.set_if_neq(leaf.map_or(Intent::Idle, |leaf| leaf.intent()));
+    }
+}
+
+/// Enemies without a ranged attack hit what they are standing next to.
+fn melee_attack(
+    time: Res<Time>,
+    attackers: Query<(Entity, &Intent, &TargetEnt, &Enemy), (Without<RangedAttack>, InPlay)>,
+    parents: Query<&ChildOf>,
+    healths: Query<(), With<Health>>,
+    mut damage: EventWriter<DamageEvent>,
+) {
+    for (attacker, intent, target, enemy) in attackers.iter() {
+        if *intent != Intent::Attack {
+            continue;
+        }
+        let Some(victim) = health_owner(target.target_ent, &parents, &healths) else {
+            continue;
+        };
+        damage.write(
+            DamageEvent::new(
+                victim,
+                enemy.melee_damage_per_second() * time.delta_secs(),
+                DamageType::Physical,
+            )
+            .with_source(attacker),
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! Behavior tree nodes and their evaluation, loaded from `*.bt.ron` files.
+//!
+//! Trees are re-evaluated from the root every tick, the first leaf that is still running decides
+//! what the enemy does. Leaves are addressed by their path of child indices so a leaf can tell
+//! whether it was already running on the previous tick.
+
+use super::Intent;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BehaviorTree {
+    pub root: BehaviorNode,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BehaviorNode {
+    /// Runs children in order until one doesn't succeed.
+    Sequence(Vec<BehaviorNode>),
+    /// Runs children in order until one doesn't fail.
+    Selector(Vec<BehaviorNode>),
+    Condition(Condition),
+    Action(Action),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    HasTarget,
+    /// Target is within attack distance.
+    TargetInRange,
+    /// Health is below this fraction of max health.
+    HealthBelow(f32),
+    Zapped,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    /// Walks up to attack distance of the target.
+    MoveTo,
+    /// Attacks the target while it is in range.
+    Attack,
+    /// Walks away from the target until `distance` away.
+    Flee {
+        distance: f32,
+    },
+    Wait {
+        secs: f32,
+    },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Failure,
+    Running,
+}
+
+/// What a tree gets to know about its enemy.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Senses {
+    /// Ground distance to the target, if there is one.
+    pub target_distance: Option<f32>,
+    pub attack_distance: f32,
+    pub health_fraction: f32,
+    pub zapped: bool,
+}
+
+impl Senses {
+    fn target_in_range(&self) -> bool {
+        self.target_distance
+            .is_some_and(|distance| distance <= self.attack_distance)
+    }
+}
+
+/// The leaf left running after a tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunningLeaf {
+    pub path: Vec<usize>,
+    pub action: Action,
+}
+
+impl RunningLeaf {
+    pub fn intent(&self) -> Intent {
+        match self.action {
+            Action::MoveTo => Intent::MoveTo,
+            Action::Attack => Intent::Attack,
+            Action::Flee { .. } => Intent::Flee,
+            Action::Wait { .. } => Intent::Idle,
+        }
+    }
+}
+
+impl BehaviorTree {
+    /// `previous` is the leaf that was running before and `elapsed` how long it has been.
+    pub fn tick(
+        &self,
+        senses: &Senses,
+        previous: Option<&[usize]>,
+        elapsed: f32,
+    ) -> (Status, Option<RunningLeaf>) {
+        let mut ctx = TickContext {
+            senses,
+            previous,
+            elapsed,
+            path: Vec::new(),
+            running: None,
+        };
+        let status = ctx.node(&self.root);
+        (status, ctx.running)
+    }
+
+    /// Human readable names of the nodes along `path`.
+    pub fn describe(&self, path: &[usize]) -> String {
+        let mut names = vec![self.root.to_string()];
+        let mut node = &self.root;
+        for &ix in path {
+            let (BehaviorNode::Sequence(children) | BehaviorNode::Selector(children)) = node else {
+                break;
+            };
+            let Some(child) = children.get(ix) else {
+                break;
+            };
+            names.push(child.to_string());
+            node = child;
+        }
+        names.join(" > ")
+    }
+}
+
+struct TickContext<'a> {
+    senses: &'a Senses,
+    previous: Option<&'a [usize]>,
+    elapsed: f32,
+    path: Vec<usize>,
+    running: Option<RunningLeaf>,
+}
+
+impl TickContext<'_> {
+    fn node(&mut self, node: &BehaviorNode) -> Status {
+        match node {
+            BehaviorNode::Sequence(children) => self.composite(children, Status::Success),
+            BehaviorNode::Selector(children) => self.composite(children, Status::Failure),
+            BehaviorNode::Condition(condition) => {
+                if self.condition(*condition) {
+                    Status::Success
+                } else {
+                    Status::Failure
+                }
+            }
+            BehaviorNode::Action(action) => {
+                let status = self.action(*action);
+                if status == Status::Running {
+                    self.running = Some(RunningLeaf {
+                        path: self.path.clone(),
+                        action: *action,
+                    });
+                }
+                status
+            }
+        }
+    }
+
+    /// Keeps going while children return `keep_going`, otherwise returns what the child did.
+    fn composite(&mut self, children: &[BehaviorNode], keep_going: Status) -> Status {
+        for (ix, child) in children.iter().enumerate() {
+            self.path.push(ix);
+            let status = self.node(child);
+            self.path.pop();
+            if status != keep_going {
+                return status;
+            }
+        }
+        keep_going
+    }
+
+    fn condition(&self, condition: Condition) -> bool {
+        let senses = self.senses;
+        match condition {
+            Condition::HasTarget => senses.target_distance.is_some(),
+            Condition::TargetInRange => senses.target_in_range(),
+            Condition::HealthBelow(fraction) => senses.health_fraction < fraction,
+            Condition::Zapped => senses.zapped,
+        }
+    }
+
+    fn action(&self, action: Action) -> Status {
+        let senses = self.senses;
+        match action {
+            Action::MoveTo => match senses.target_distance {
+                None => Status::Failure,
+                Some(_) if senses.target_in_range() => Status::Success,
+                Some(_) => Status::Running,
+            },
+            Action::Attack => {
+                if senses.target_in_range() {
+                    Status::Running
+                } else {
+                    Status::Failure
+                }
+            }
+            Action::Flee { distance } => match senses.target_distance {
+                None => Status::Failure,
+                Some(current) if current >= distance => Status::Success,
+                Some(_) => Status::Running,
+            },
+            Action::Wait { secs } => {
+                let waited = if self.previous == Some(self.path.as_slice()) {
+                    self.elapsed
+                } else {
+                    0.0
+                };
+                if waited >= secs {
+                    Status::Success
+                } else {
+                    Status::Running
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for BehaviorNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sequence(_) => write!(f, "Sequence"),
+            Self::Selector(_) => write!(f, "Selector"),
+            Self::Condition(condition) => write!(f, "{condition:?}"),
+            Self::Action(action) => write!(f, "{action:?}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BehaviorTreeLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for BehaviorTreeLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read behavior tree: {err}"),
+            Self::Ron(err) => write!(f, "could not parse behavior tree: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BehaviorTreeLoaderError {}
+
+impl From<std::io::Error> for BehaviorTreeLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for BehaviorTreeLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Ron(err)
+    }
+}
+
+#[derive(Default)]
+pub struct BehaviorTreeLoader;
+
+impl AssetLoader for BehaviorTreeLoader {
+    type Asset = BehaviorTree;
+    type Settings = ();
+    type Error = BehaviorTreeLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bt.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn melee() -> BehaviorTree {
+        BehaviorTree {
+            root: BehaviorNode::Selector(vec![
+                BehaviorNode::Sequence(vec![
+                    BehaviorNode::Condition(Condition::HealthBelow(0.25)),
+                    BehaviorNode::Action(Action::Flee { distance: 100.0 }),
+                ]),
+                BehaviorNode::Sequence(vec![
+                    BehaviorNode::Action(Action::MoveTo),
+                    BehaviorNode::Action(Action::Attack),
+                ]),
+                BehaviorNode::Action(Action::Wait { secs: 1.0 }),
+            ]),
+        }
+    }
+
+    fn senses(target_distance: Option<f32>, health_fraction: f32) -> Senses {
+        Senses {
+            target_distance,
+            attack_distance: 20.0,
+            health_fraction,
+            zapped: false,
+        }
+    }
+
+    #[test]
+    fn moves_then_attacks() {
+        let tree = melee();
+        let (status, leaf) = tree.tick(&senses(Some(50.0), 1.0), None, 0.0);
+        assert_eq!(status, Status::Running);
+        assert_eq!(leaf.unwrap().intent(), Intent::MoveTo);
+
+        let (status, leaf) = tree.tick(&senses(Some(10.0), 1.0), None, 0.0);
+        assert_eq!(status, Status::Running);
+        let leaf = leaf.unwrap();
+        assert_eq!(leaf.intent(), Intent::Attack);
+        assert_eq!(leaf.path, vec![1, 1]);
+    }
+
+    #[test]
+    fn flees_when_hurt() {
+        let (_, leaf) = melee().tick(&senses(Some(10.0), 0.1), None, 0.0);
+        assert_eq!(leaf.unwrap().intent(), Intent::Flee);
+        // Far enough away, stays there.
+        let (status, leaf) = melee().tick(&senses(Some(150.0), 0.1), None, 0.0);
+        assert_eq!(status, Status::Success);
+        assert!(leaf.is_none());
+    }
+
+    #[test]
+    fn waits_without_target() {
+        let tree = melee();
+        let (status, leaf) = tree.tick(&senses(None, 1.0), None, 0.0);
+        assert_eq!(status, Status::Running);
+        let path = leaf.unwrap().path;
+        assert_eq!(path, vec![2]);
+        let (status, _) = tree.tick(&senses(None, 1.0), Some(&path), 0.5);
+        assert_eq!(status, Status::Running);
+        let (status, leaf) = tree.tick(&senses(None, 1.0), Some(&path), 1.0);
+        assert_eq!(status, Status::Success);
+        assert!(leaf.is_none());
+    }
+
+    #[test]
+    fn describes_path() {
+        assert_eq!(melee().describe(&[1, 1]), "Selector > Sequence > Attack");
+    }
+
+    #[test]
+    fn parses_ron() {
+        let tree: BehaviorTree = ron::de::from_str(
+            "(root: Selector([Sequence([Condition(Zapped), Action(Flee(distance: 5.0))]), Action(Wait(secs: 1.0))]))",
+        )
+        .unwrap();
+        assert_eq!(
+            tree.describe(&[0, 1]),
+            "Selector > Sequence > Flee { distance: 5.0 }"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Crowd steering so enemies heading for the same target spread out instead of stacking up.
+//!
+//! Each [`Flocking`] entity steers towards its [`TargetEnt`] (or away from it when fleeing) while
+//! keeping apart from, lining up with and sticking to its neighbors. Neighbors are looked up in the
+//! [`SpatialIndex`](crate::game::spatial::SpatialIndex).
+
+use super::MovementSpeed;
+use super::brain::Intent;
+use super::target_ent::{TargetEnt, target_ent_sys};
+use crate::game::pause_controller::PausableSystems;
+use crate::game::prefabs::enemy_pool::InPlay;
+use crate::game::spatial::Nearby;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct FlockingConfig {
+    /// How far away other enemies count as neighbors.
+    #[default(40.0)]
+    pub neighbor_radius: f32,
+    /// Neighbors closer than this are pushed away.
+    #[default(20.0)]
+    pub separation_radius: f32,
+    #[default(1.5)]
+    pub separation_weight: f32,
+    #[default(0.3)]
+    pub alignment_weight: f32,
+    #[default(0.2)]
+    pub cohesion_weight: f32,
+    /// Distance before the attack distance at which enemies start slowing down.
+    #[default(30.0)]
+    pub arrival_radius: f32,
+    /// Fraction of the movement speed the velocity can change by per second.
+    #[default(4.0)]
+    pub responsiveness: f32,
+}
+
+/// Steers with the crowd, [`target_ent_sys`] moves the entity by its velocity.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Flocking {
+    /// Ground velocity, y is always zero.
+    pub velocity: Vec3,
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        flock.before(target_ent_sys).in_set(PausableSystems),
+    );
+}
+
+/// Velocity towards (or away from) the target, slowing down on arrival.
+fn goal_velocity(
+    config: &FlockingConfig,
+    position: Vec2,
+    target: Vec2,
+    within_distance: f32,
+    speed: f32,
+    intent: Intent,
+) -> Vec2 {
+    let offset = target - position;
+    let distance = offset.length();
+    let direction = offset.normalize_or_zero();
+    match intent {
+        Intent::MoveTo => {
+            let remaining = distance - within_distance;
+            direction
+                * speed
+                * (remaining / config.arrival_radius.max(f32::EPSILON)).clamp(0.0, 1.0)
+        }
+        Intent::Flee => -direction * speed,
+        Intent::Idle | Intent::Attack => Vec2::ZERO,
+    }
+}
+
+pub(crate) fn flock(
+    time: Res<Time>,
+    config: Res<FlockingConfig>,
+    mut boids: Query<
+        (
+            Entity,
+            &mut Flocking,
+            &GlobalTransform,
+            &MovementSpeed,
+            Option<&TargetEnt>,
+            Option<&Intent>,
+        ),
+        InPlay,
+    >,
+    targets: Query<&GlobalTransform>,
+    neighbors: Nearby<(With<Flocking>, InPlay)>,
+) {
+    // Everyone steers off the same velocities.
+    let velocities = boids
+        .iter()
+        .map(|(entity, flocking, ..)| (entity, flocking.velocity.xz()))
+        .collect::<HashMap<_, _>>();
+
+    for (entity, mut flocking, transform, speed, target, intent) in boids.iter_mut() {
+        let position = transform.translation().xz();
+        let speed = speed.0;
+
+        let mut separation = Vec2::ZERO;
+        let mut velocity_sum = Vec2::ZERO;
+        let mut position_sum = Vec2::ZERO;
+        let mut count = 0;
+        for (other, other_position) in
+            neighbors.within_radius(transform.translation(), config.neighbor_radius)
+        {
+            if other == entity {
+                continue;
+            }
+            let other_position = other_position.xz();
+            let offset = position - other_position;
+            let distance = offset.length();
+            if distance < config.separation_radius {
+                // Stacked enemies get pushed apart in some direction.
+                let away = offset.try_normalize().unwrap_or(Vec2::X);
+                separation += away * (1.0 - distance / config.separation_radius);
+            }
+            velocity_sum += velocities.get(&other).copied().unwrap_or_default();
+            position_sum += other_position;
+            count += 1;
+        }
+
+        let goal = target
+            .and_then(|target| {
+                let target_position = targets.get(target.target_ent).ok()?.translation().xz();
+                Some(goal_velocity(
+                    &config,
+                    position,
+                    target_position,
+                    target.within_distance,
+                    speed,
+                    intent.copied().unwrap_or(Intent::MoveTo),
+                ))
+            })
+            .unwrap_or(Vec2::ZERO);
+        let mut desired = goal + separation * config.separation_weight * speed;
+        if count > 0 {
+            let count = count as f32;
+            let alignment = velocity_sum / count - flocking.velocity.xz();
+            let cohesion = (position_sum / count - position).normalize_or_zero() * speed;
+            desired += alignment * config.alignment_weight + cohesion * config.cohesion_weight;
+        }
+        let desired = desired.clamp_length_max(speed);
+
+        let current = flocking.velocity.xz();
+        let max_change = speed * config.responsiveness * time.delta_secs();
+        let velocity = current + (desired - current).clamp_length_max(max_change);
+        flocking.velocity = Vec3::new(velocity.x, 0.0, velocity.y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrives_at_attack_distance() {
+        let config = FlockingConfig::default();
+        let goal = |distance: f32| {
+            goal_velocity(
+                &config,
+                Vec2::ZERO,
+                Vec2::new(distance, 0.0),
+                20.0,
+                8.0,
+                Intent::MoveTo,
+            )
+        };
+        assert_eq!(goal(200.0), Vec2::new(8.0, 0.0));
+        assert!(goal(35.0).x < 8.0 && goal(35.0).x > 0.0);
+        assert_eq!(goal(20.0), Vec2::ZERO);
+        let flee = goal_velocity(
+            &config,
+            Vec2::ZERO,
+            Vec2::new(10.0, 0.0),
+            20.0,
+            8.0,
+            Intent::Flee,
+        );
+        assert_eq!(flee, Vec2::new(-8.0, 0.0));
+    }
+}
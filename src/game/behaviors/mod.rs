@@ -1,5 +1,9 @@
+pub mod brain;
+pub mod flocking;
+pub mod ranged_attack;
 pub mod spawn;
 pub mod target_ent;
+pub mod target_select;
 
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
@@ -11,6 +15,10 @@ pub struct MovementSpeed(pub f32);
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
+    app.add_plugins(brain::plugin);
+    app.add_plugins(flocking::plugin);
+    app.add_plugins(ranged_attack::plugin);
     app.add_plugins(spawn::plugin);
     app.add_plugins(target_ent::plugin);
+    app.add_plugins(target_select::plugin);
 }
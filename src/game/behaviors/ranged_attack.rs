@@ -0,0 +1,70 @@
+use super::brain::Intent;
+use super::target_ent::TargetEnt;
+use crate::game::pause_controller::PausableSystems;
+use crate::game::prefabs::projectile::{FireProjectile, ProjectileTeam, ballistic_velocity};
+use avian3d::prelude::Gravity;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+/// Shoots projectiles at the [`TargetEnt`] once it is within `range`.
+#[auto_register_type]
+#[derive(Component, Debug, SmartDefault, Clone, Reflect)]
+#[reflect(Component)]
+pub struct RangedAttack {
+    #[default(Timer::from_seconds(3.0, TimerMode::Repeating))]
+    pub cooldown: Timer,
+    #[default(200.0)]
+    pub range: f32,
+    #[default(10.0)]
+    pub damage: f32,
+    /// Horizontal speed of the projectile.
+    #[default(80.0)]
+    pub speed: f32,
+    /// Where the projectile leaves the shooter, relative to its origin.
+    #[default(Vec3::Y * 30.0)]
+    pub muzzle_offset: Vec3,
+}
+
+fn ranged_attack(
+    time: Res<Time>,
+    gravity: Res<Gravity>,
+    mut attackers: Query<(
+        Entity,
+        &mut RangedAttack,
+        &TargetEnt,
+        &GlobalTransform,
+        Option<&Intent>,
+    )>,
+    targets: Query<&GlobalTransform>,
+    mut fire: EventWriter<FireProjectile>,
+) {
+    for (entity, mut attack, target, transform, intent) in attackers.iter_mut() {
+        attack.cooldown.tick(time.delta());
+        // Enemies with a brain only shoot when it tells them to.
+        if !attack.cooldown.finished() || intent.is_some_and(|intent| *intent != Intent::Attack) {
+            continue;
+        }
+        let Ok(target_transform) = targets.get(target.target_ent) else {
+            continue;
+        };
+        let origin = transform.translation() + attack.muzzle_offset;
+        let target = target_transform.translation();
+        if origin.xz().distance(target.xz()) > attack.range {
+            continue;
+        }
+        let flight_secs = origin.xz().distance(target.xz()) / attack.speed;
+        fire.write(FireProjectile {
+            origin,
+            velocity: ballistic_velocity(origin, target, flight_secs, gravity.0),
+            damage: attack.damage,
+            team: ProjectileTeam::Enemy,
+            source: Some(entity),
+        });
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(FixedUpdate, ranged_attack.in_set(PausableSystems));
+}
@@ -2,46 +2,111 @@ use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
 use crate::game::{
-    prefabs::{spawner::Spawner, tower::Tower},
+    camera::cut::CameraCut,
+    pause_controller::PausableSystems,
+    prefabs::{enemy_pool::EnemyPool, spawner::Spawner},
+    rng::{sphere::sample_point_in_disc, stream::GameplayRng},
     scenes::game::LevelRoot,
+    screens::Screen,
+    terrain::heightmap::Heightmap,
 };
 
-use super::target_ent::TargetEnt;
+/// How long the camera shows the spawner that starts a new wave.
+const WAVE_CUT_HOLD_SECS: f32 = 1.5;
+const WAVE_CUT_RADIUS: f32 = 250.0;
+/// Spawns further apart than this belong to different waves.
+const WAVE_GAP_SECS: f32 = 8.0;
+/// Enemies come out somewhere within this distance of their spawner.
+const SPAWN_SCATTER_RADIUS: f32 = 15.0;
+
+/// Enemies come in waves: spawns follow each other until every spawner went quiet for a while,
+/// the next spawn after that starts a new wave.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct Waves {
+    /// Waves started so far in the level.
+    pub count: u32,
+    last_spawn_secs: Option<f32>,
+}
+
+impl Waves {
+    /// Records a spawn at `now_secs` and returns whether it starts a new wave.
+    pub fn spawned(&mut self, now_secs: f32, gap_secs: f32) -> bool {
+        let new_wave = self
+            .last_spawn_secs
+            .is_none_or(|last| now_secs - last > gap_secs);
+        if new_wave {
+            self.count += 1;
+        }
+        self.last_spawn_secs = Some(now_secs);
+        new_wave
+    }
+}
 
 fn spawn(
     mut commands: Commands,
     time: Res<Time>,
-    level_ent_q: Single<Entity, With<LevelRoot>>,
-    tower_ent_q: Single<Entity, With<Tower>>,
-    mut spawners: Query<(&mut Spawner, &Transform)>,
+    heightmap: Option<Res<Heightmap>>,
+    // Only spawn while a level is loaded.
+    _level_root: Single<(), With<LevelRoot>>,
+    mut pool: ResMut<EnemyPool>,
+    mut waves: ResMut<Waves>,
+    mut spawners: Query<(&mut Spawner, &Transform, &mut GameplayRng)>,
+    mut camera_cuts: EventWriter<CameraCut>,
 ) {
-    let level_ent = level_ent_q.into_inner();
-    let tower_ent = tower_ent_q.into_inner();
-    for (mut spawner, trans) in spawners.iter_mut() {
+    let now = time.elapsed_secs();
+    for (mut spawner, trans, mut rng) in spawners.iter_mut() {
         if spawner.spawn_left == 0 {
-            return;
+            continue;
         }
         if spawner.time_to_next_spawn.is_zero() {
-            commands.entity(level_ent).with_child((
-                Name::new("Skele"),
-                spawner.spawns,
-                // TODO scale should be set in the enemy spawner
-                trans.with_scale(Vec3::splat(15.)),
-                TargetEnt {
-                    target_ent: tower_ent,
-                    // TODO migrate to using colliders for determining proximity
-                    within_distance: 20.0,
-                },
-            ));
+            let mut trans = *trans;
+            // Rolled on the spawner's own stream so the spawns replay with the seed.
+            let enemy = spawner.next_enemy(&mut **rng);
+            let scatter = sample_point_in_disc(&mut **rng) * SPAWN_SCATTER_RADIUS;
+            trans.translation += Vec3::new(scatter.x, 0.0, scatter.y);
+            if let Some(heightmap) = heightmap.as_ref() {
+                trans.translation.y = heightmap.height_at(trans.translation.xz());
+            }
+            // Show where a new wave comes from.
+            if waves.spawned(now, WAVE_GAP_SECS) {
+                camera_cuts.write(
+                    CameraCut::new(trans.translation, WAVE_CUT_HOLD_SECS)
+                        .with_radius(WAVE_CUT_RADIUS),
+                );
+            }
+            pool.spawn(&mut commands, enemy, trans.translation);
 
             spawner.spawn_left -= 1;
             spawner.time_to_next_spawn = spawner.spawn_duration;
         }
+        // Fixed time follows virtual time, which slows down with the time scale and stands
+        // still while paused, so does the countdown.
         spawner.time_to_next_spawn = spawner.time_to_next_spawn.saturating_sub(time.delta());
     }
 }
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
-    app.add_systems(Update, spawn);
+    app.add_systems(FixedUpdate, spawn.in_set(PausableSystems));
+    app.add_systems(OnEnter(Screen::Gameplay), |mut waves: ResMut<Waves>| {
+        *waves = Waves::default();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quiet_gap_starts_new_wave() {
+        let mut waves = Waves::default();
+        assert!(waves.spawned(0.0, 8.0));
+        assert!(!waves.spawned(3.0, 8.0));
+        assert!(!waves.spawned(10.0, 8.0));
+        assert!(waves.spawned(20.0, 8.0));
+        assert_eq!(waves.count, 2);
+    }
 }
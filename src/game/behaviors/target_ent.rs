@@ -1,5 +1,8 @@
 use super::MovementSpeed;
+use super::brain::Intent;
+use super::flocking::Flocking;
 use crate::game::pause_controller::PausableSystems;
+use crate::game::terrain::heightmap::Heightmap;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
@@ -11,51 +14,93 @@ pub struct TargetEnt {
     pub within_distance: f32,
 }
 
-fn target_ent_sys(
+pub(crate) fn target_ent_sys(
     mut commands: Commands,
     time: Res<Time>,
-    target_q: Query<(Entity, &TargetEnt, Option<&MovementSpeed>)>,
+    heightmap: Option<Res<Heightmap>>,
+    target_q: Query<(
+        Entity,
+        &TargetEnt,
+        Option<&MovementSpeed>,
+        Option<&Intent>,
+        Option<&Flocking>,
+    )>,
+    global_transform_q: Query<&GlobalTransform>,
     mut transform_q: Query<&mut Transform>,
 ) {
-    for (self_ent, &target, movement_speed) in target_q.iter() {
-        // TODO remove need for taking this into consideration
-        let ground_height = 10.0;
+    let ground_height = |xz: Vec2| {
+        heightmap
+            .as_ref()
+            .map_or(0.0, |heightmap| heightmap.height_at(xz))
+    };
+    for (self_ent, &target, movement_speed, intent, flocking) in target_q.iter() {
         let target_ent = target.target_ent;
         // If target ent no longer exists, remove component
-        let Ok(target_trans) = transform_q.get(target_ent).cloned() else {
+        // Targets may be nested (e.g. the wizard on the tower), so use world space.
+        let Ok(target_trans) = global_transform_q
+            .get(target_ent)
+            .map(|target| target.compute_transform())
+        else {
             commands.entity(self_ent).remove::<TargetEnt>();
-            return;
+            continue;
         };
         // Remove y component as some objects are not at ground level (e.g.
         // tower center is at this point in time in the middle of its mesh).
         let target_trans = target_trans.with_translation(Vec3::new(
             target_trans.translation.x,
-            ground_height,
+            ground_height(target_trans.translation.xz()),
             target_trans.translation.z,
         ));
 
-        // Face target
         let mut self_trans = transform_q.get_mut(self_ent).unwrap();
-        self_trans.look_at(target_trans.translation, Vec3::Y);
-
-        // If target is outside range (`within_distance`), move towards it,
-        // otherwise attack.
         let dist = self_trans.translation.distance(target_trans.translation);
-        if dist > target.within_distance {
-            if let Some(move_speed) = movement_speed {
-                let move_speed = move_speed.0 * time.delta_secs();
+        // Without a brain, enemies always close in on their target.
+        let intent = intent.copied().unwrap_or(Intent::MoveTo);
+        if let Some(flocking) = flocking {
+            // The crowd already worked out where to go.
+            let look_at = match intent {
+                Intent::Flee => self_trans.translation + flocking.velocity,
+                _ => target_trans.translation,
+            };
+            self_trans.translation += flocking.velocity * time.delta_secs();
+            self_trans.translation.y = ground_height(self_trans.translation.xz());
+            if look_at.xz() != self_trans.translation.xz() {
+                self_trans.look_at(look_at.with_y(self_trans.translation.y), Vec3::Y);
+            }
+            continue;
+        }
+        let Some(move_speed) = movement_speed.map(|speed| speed.0 * time.delta_secs()) else {
+            self_trans.look_at(target_trans.translation, Vec3::Y);
+            continue;
+        };
+        match intent {
+            // If target is outside range (`within_distance`), move towards it.
+            Intent::MoveTo if dist > target.within_distance => {
+                self_trans.look_at(target_trans.translation, Vec3::Y);
                 let move_dist = move_speed.min(dist - target.within_distance);
                 self_trans.translation = self_trans
                     .translation
                     .move_towards(target_trans.translation, move_dist);
             }
-        } else {
-            // TODO trigger attack
+            Intent::Flee => {
+                let away = (self_trans.translation - target_trans.translation)
+                    .with_y(0.0)
+                    .normalize_or(Vec3::X);
+                let look_at = self_trans.translation + away;
+                self_trans.look_at(look_at, Vec3::Y);
+                self_trans.translation += away * move_speed;
+            }
+            _ => {
+                self_trans.look_at(target_trans.translation, Vec3::Y);
+                continue;
+            }
         }
+        // Stick to the terrain instead of cutting through hills.
+        self_trans.translation.y = ground_height(self_trans.translation.xz());
     }
 }
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
-    app.add_systems(Update, target_ent_sys.in_set(PausableSystems));
+    app.add_systems(FixedUpdate, target_ent_sys.in_set(PausableSystems));
 }
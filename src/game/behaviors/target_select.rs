@@ -0,0 +1,248 @@
+//! Enemies pick what to attack among the [`DefenseTarget`]s and keep reconsidering.
+
+use super::target_ent::TargetEnt;
+use crate::game::health::{Dead, Health, MaxHealth};
+use crate::game::pause_controller::PausableSystems;
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::prefabs::enemy_pool::InPlay;
+use crate::game::prefabs::tower::Tower;
+use crate::game::spark::ZappedBy;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct TargetSelectionConfig {
+    /// Seconds between re-evaluations of the current target.
+    #[default(2.0)]
+    pub reevaluate_secs: f32,
+    /// Score per point of [`DefenseTarget::threat`].
+    #[default(100.0)]
+    pub threat_weight: f32,
+    /// Score lost per unit of distance.
+    #[default(0.2)]
+    pub distance_weight: f32,
+    /// Score for a target at zero health, scaled down for healthier ones.
+    #[default(50.0)]
+    pub damage_weight: f32,
+    /// How much better another target has to score before switching to it.
+    #[default(20.0)]
+    pub switch_threshold: f32,
+    /// How long a provoked enemy sticks to whatever provoked it.
+    #[default(5.0)]
+    pub provoked_secs: f32,
+}
+
+/// Something enemies can decide to attack.
+#[auto_register_type]
+#[derive(Component, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct DefenseTarget {
+    /// How much enemies want this gone compared to other targets.
+    #[default(1.0)]
+    pub threat: f32,
+}
+
+impl DefenseTarget {
+    pub fn new(threat: f32) -> Self {
+        Self { threat }
+    }
+}
+
+/// Makes an enemy choose its [`TargetEnt`] among the [`DefenseTarget`]s.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct TargetSelector {
+    reevaluate: Timer,
+    /// Set while the enemy is locked onto whatever provoked it.
+    provoked: Option<Timer>,
+}
+
+impl Default for TargetSelector {
+    fn default() -> Self {
+        // Starts finished so new enemies pick a target right away.
+        let mut reevaluate = Timer::from_seconds(0.0, TimerMode::Once);
+        reevaluate.tick(std::time::Duration::ZERO);
+        Self {
+            reevaluate,
+            provoked: None,
+        }
+    }
+}
+
+/// Triggered on an enemy to lock it onto `source` for a while, e.g. whatever attacked it.
+#[derive(Event, Debug, Copy, Clone)]
+pub struct Provoked {
+    pub source: Entity,
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_observer(on_provoked);
+    app.add_observer(on_enemy_zapped);
+    app.add_systems(FixedUpdate, select_targets.in_set(PausableSystems));
+}
+
+fn score(
+    config: &TargetSelectionConfig,
+    from: Vec3,
+    to: Vec3,
+    target: &DefenseTarget,
+    health: Option<(&Health, &MaxHealth)>,
+) -> f32 {
+    let damage = health.map_or(0.0, |(health, max)| {
+        (1.0 - health.0 / max.0.max(f32::EPSILON)).clamp(0.0, 1.0)
+    });
+    target.threat * config.threat_weight - from.xz().distance(to.xz()) * config.distance_weight
+        + damage * config.damage_weight
+}
+
+fn select_targets(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<TargetSelectionConfig>,
+    mut enemies: Query<
+        (
+            Entity,
+            &Enemy,
+            &GlobalTransform,
+            &mut TargetSelector,
+            Option<&TargetEnt>,
+        ),
+        InPlay,
+    >,
+    targets: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &DefenseTarget,
+            Option<(&Health, &MaxHealth)>,
+        ),
+        Without<Dead>,
+    >,
+    alive: Query<(), Without<Dead>>,
+) {
+    for (entity, enemy, transform, mut selector, current) in enemies.iter_mut() {
+        if let Some(provoked) = selector.provoked.as_mut() {
+            let still_there = current.is_some_and(|current| alive.contains(current.target_ent));
+            if !provoked.tick(time.delta()).finished() && still_there {
+                continue;
+            }
+            selector.provoked = None;
+        }
+        let lost_target = current.is_none_or(|current| !targets.contains(current.target_ent));
+        if !selector.reevaluate.tick(time.delta()).finished() && !lost_target {
+            continue;
+        }
+        selector.reevaluate = Timer::from_seconds(config.reevaluate_secs, TimerMode::Once);
+
+        let from = transform.translation();
+        let scored = targets
+            .iter()
+            .map(|(target, target_transform, defense, health)| {
+                let score = score(
+                    &config,
+                    from,
+                    target_transform.translation(),
+                    defense,
+                    health,
+                );
+                (target, score)
+            });
+        let Some((best, best_score)) = scored.max_by(|a, b| a.1.total_cmp(&b.1)) else {
+            continue;
+        };
+        // Only switch for a clearly better target so enemies don't dither between two.
+        if let Some(current) = current.filter(|_| !lost_target) {
+            if current.target_ent == best {
+                continue;
+            }
+            let (_, current_transform, defense, health) =
+                targets.get(current.target_ent).expect("checked above");
+            let current_score = score(
+                &config,
+                from,
+                current_transform.translation(),
+                defense,
+                health,
+            );
+            if best_score < current_score + config.switch_threshold {
+                continue;
+            }
+        }
+        commands.entity(entity).insert(TargetEnt {
+            target_ent: best,
+            within_distance: enemy.attack_distance(),
+        });
+    }
+}
+
+fn on_provoked(
+    trigger: Trigger<Provoked>,
+    mut commands: Commands,
+    config: Res<TargetSelectionConfig>,
+    mut enemies: Query<(&Enemy, &mut TargetSelector), InPlay>,
+) {
+    let Ok((enemy, mut selector)) = enemies.get_mut(trigger.target()) else {
+        return;
+    };
+    selector.provoked = Some(Timer::from_seconds(config.provoked_secs, TimerMode::Once));
+    commands.entity(trigger.target()).insert(TargetEnt {
+        target_ent: trigger.source,
+        within_distance: enemy.attack_distance(),
+    });
+}
+
+/// Zapped enemies rush the nearest standing tower. The spark zapping them ends up attached to
+/// them, so going after the spark would have them attack themselves.
+fn on_enemy_zapped(
+    trigger: Trigger<OnInsert, ZappedBy>,
+    mut commands: Commands,
+    enemies: Query<&GlobalTransform, (With<TargetSelector>, InPlay)>,
+    towers: Query<(Entity, &GlobalTransform), (With<Tower>, Without<Dead>)>,
+) {
+    let Ok(transform) = enemies.get(trigger.target()) else {
+        return;
+    };
+    let from = transform.translation();
+    let nearest = towers.iter().min_by(|(_, a), (_, b)| {
+        a.translation()
+            .distance_squared(from)
+            .total_cmp(&b.translation().distance_squared(from))
+    });
+    if let Some((tower, _)) = nearest {
+        commands
+            .entity(trigger.target())
+            .trigger(Provoked { source: tower });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threat_distance_and_damage_score() {
+        let config = TargetSelectionConfig::default();
+        let target = DefenseTarget::default();
+        let score = |to: Vec3, health| score(&config, Vec3::ZERO, to, &target, health);
+
+        // Height doesn't count towards the distance.
+        assert_eq!(score(Vec3::new(0.0, 50.0, 100.0), None), 80.0);
+        assert!(score(Vec3::X * 50.0, None) > score(Vec3::X * 100.0, None));
+
+        let wounded = (Health(25.0), MaxHealth(100.0));
+        let wounded_score = score(Vec3::X * 100.0, Some((&wounded.0, &wounded.1)));
+        assert_eq!(wounded_score, 80.0 + 0.75 * config.damage_weight);
+
+        let threat = DefenseTarget::new(1.5);
+        assert!(
+            super::score(&config, Vec3::ZERO, Vec3::X * 200.0, &threat, None)
+                > score(Vec3::X * 100.0, None)
+        );
+    }
+}
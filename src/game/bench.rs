@@ -0,0 +1,201 @@
+//! Headless worlds for the criterion benches in `benches/`, only built with the `bench` feature.
+//!
+//! Every [`SystemBench`] runs a single gameplay system. [`SystemBench::prepare`] gets the world
+//! ready for the next run (advancing time, moving things) and is kept out of the measurement.
+
+use crate::game::behaviors::MovementSpeed;
+use crate::game::behaviors::brain::Intent;
+use crate::game::behaviors::flocking::{Flocking, FlockingConfig, flock};
+use crate::game::behaviors::target_ent::{TargetEnt, target_ent_sys};
+use crate::game::effects::bolt::Bolt;
+use crate::game::effects::lightning_ball::{
+    DEFAULT_LIGHTNING_BALL_BOLT_LIFETIME_SECS, LightningBall, LightningBallConduit,
+    LightningBallMeshCache, LightningBallMeshMaterialCache, animate_in_range,
+    on_lightning_ball_added, tick_bolt_timers,
+};
+use crate::game::health::AdjustHp;
+use crate::game::rng::stream::RngStreams;
+use crate::game::spark::Spark;
+use crate::game::spark::config::SparkConfig;
+use crate::game::spark::spark::apply_distance_cost;
+use crate::game::spatial::SpatialIndex;
+use avian3d::prelude::{Collider, PhysicsPlugins, RigidBody};
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+const FRAME: Duration = Duration::from_millis(16);
+
+pub struct SystemBench {
+    world: World,
+    prepare: Schedule,
+    run: Schedule,
+}
+
+impl SystemBench {
+    fn new(world: World) -> Self {
+        let mut prepare = Schedule::default();
+        let mut run = Schedule::default();
+        // Timings shouldn't depend on how the thread pool feels today.
+        prepare.set_executor_kind(ExecutorKind::SingleThreaded);
+        run.set_executor_kind(ExecutorKind::SingleThreaded);
+        Self {
+            world,
+            prepare,
+            run,
+        }
+    }
+
+    /// Gets the world ready for the next [`run`](Self::run).
+    pub fn prepare(&mut self) {
+        self.prepare.run(&mut self.world);
+    }
+
+    /// Runs the system under test once.
+    pub fn run(&mut self) {
+        self.run.run(&mut self.world);
+    }
+}
+
+fn advance_time(mut time: ResMut<Time>) {
+    time.advance_by(FRAME);
+}
+
+/// Points spread evenly on a circle around the origin.
+fn ring(count: usize, radius: f32) -> impl Iterator<Item = Vec3> {
+    (0..count).map(move |ix| {
+        let angle = ix as f32 / count.max(1) as f32 * TAU;
+        Vec3::new(angle.cos(), 0.0, angle.sin()) * radius
+    })
+}
+
+/// `enemies` flocking up to a tower from far away under `target_ent_sys`.
+pub fn target_ent(enemies: usize) -> SystemBench {
+    let mut world = World::new();
+    world.insert_resource(Time::<()>::default());
+    world.init_resource::<FlockingConfig>();
+    world.init_resource::<SpatialIndex>();
+    let tower = world
+        .spawn((Transform::default(), GlobalTransform::default()))
+        .id();
+    world.spawn_batch(ring(enemies, 1000.0).map(move |translation| {
+        (
+            Transform::from_translation(translation),
+            GlobalTransform::from_translation(translation),
+            TargetEnt {
+                target_ent: tower,
+                within_distance: 20.0,
+            },
+            MovementSpeed(8.0),
+            Intent::MoveTo,
+            Flocking::default(),
+        )
+    }));
+
+    /// Stands in for transform propagation and the spatial index update between frames.
+    fn sync_positions(
+        mut index: ResMut<SpatialIndex>,
+        mut enemies: Query<(Entity, &Transform, &mut GlobalTransform), With<Flocking>>,
+    ) {
+        for (entity, transform, mut global_transform) in enemies.iter_mut() {
+            *global_transform = (*transform).into();
+            index.insert(entity, transform.translation);
+        }
+    }
+
+    let mut bench = SystemBench::new(world);
+    // Steering is worked out by `flock` before `target_ent_sys` moves anyone, like in game.
+    bench
+        .prepare
+        .add_systems((advance_time, sync_positions, flock).chain());
+    bench.run.add_systems(target_ent_sys);
+    bench
+}
+
+/// `sparks` that all moved since the last frame under `apply_distance_cost`.
+pub fn distance_cost(sparks: usize) -> SystemBench {
+    let mut world = World::new();
+    world.init_resource::<SparkConfig>();
+    world.init_resource::<Events<AdjustHp>>();
+    world.spawn_batch(ring(sparks, 100.0).map(|translation| {
+        (
+            Spark,
+            Transform::from_translation(translation),
+            GlobalTransform::from_translation(translation),
+        )
+    }));
+
+    fn move_sparks(
+        mut sparks: Query<&mut GlobalTransform, With<Spark>>,
+        mut adjust_hp: ResMut<Events<AdjustHp>>,
+    ) {
+        // Nothing reads the events here, don't let them pile up.
+        adjust_hp.clear();
+        for mut transform in sparks.iter_mut() {
+            *transform = GlobalTransform::from_translation(transform.translation() + Vec3::X);
+        }
+    }
+
+    let mut bench = SystemBench::new(world);
+    bench.prepare.add_systems(move_sparks);
+    bench.run.add_systems(apply_distance_cost);
+    bench
+}
+
+/// `balls` lightning balls with `conduits` conduits each in range under `animate_in_range`.
+pub fn lightning_balls(balls: usize, conduits: usize) -> SystemBench {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        AssetPlugin::default(),
+        // Contacts are only needed once, find them right away instead of on a fixed step.
+        PhysicsPlugins::new(PostUpdate),
+    ));
+    app.init_asset::<Mesh>();
+    app.init_asset::<StandardMaterial>();
+    app.init_resource::<RngStreams>();
+    app.init_resource::<LightningBallMeshCache>();
+    app.init_resource::<LightningBallMeshMaterialCache>();
+    app.add_observer(on_lightning_ball_added);
+
+    for ball in 0..balls {
+        // Far enough apart that balls don't reach each other's conduits.
+        let center = Vec3::X * ball as f32 * 200.0;
+        app.world_mut()
+            .spawn((LightningBall, Transform::from_translation(center)));
+        for conduit in ring(conduits, 20.0) {
+            app.world_mut().spawn((
+                LightningBallConduit,
+                RigidBody::Static,
+                Collider::sphere(2.0),
+                Transform::from_translation(center + conduit),
+            ));
+        }
+    }
+    app.finish();
+    app.cleanup();
+    // Propagate transforms and let physics collect the contacts.
+    app.update();
+    app.update();
+
+    fn despawn_bolts(mut commands: Commands, bolts: Query<Entity, With<Bolt>>) {
+        for entity in bolts.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    fn advance_bolt_timers(mut time: ResMut<Time>) {
+        time.advance_by(Duration::from_secs_f32(
+            DEFAULT_LIGHTNING_BALL_BOLT_LIFETIME_SECS,
+        ));
+    }
+
+    let mut bench = SystemBench::new(std::mem::take(app.world_mut()));
+    bench
+        .prepare
+        .add_systems((despawn_bolts, advance_bolt_timers, tick_bolt_timers).chain());
+    bench.run.add_systems(animate_in_range);
+    bench
+}
@@ -0,0 +1,105 @@
+//! Cinematic cuts that briefly take the camera away from the current [`CameraMode`].
+
+use crate::game::camera::mode::CameraMode;
+use crate::game::camera::{CameraSystems, MainCamera};
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use bevy_panorbit_camera::PanOrbitCamera;
+
+/// Jumps the camera to `focus` and holds it there for `hold_secs`, afterwards the current
+/// [`CameraMode`] eases it back. A new cut replaces one that is still running.
+#[auto_register_type]
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct CameraCut {
+    pub focus: Vec3,
+    /// Distance to the focus during the cut, keeps the current one if `None`.
+    pub radius: Option<f32>,
+    pub hold_secs: f32,
+}
+
+impl CameraCut {
+    pub fn new(focus: Vec3, hold_secs: f32) -> Self {
+        Self {
+            focus,
+            radius: None,
+            hold_secs,
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = Some(radius);
+        self
+    }
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ActiveCameraCut {
+    hold: Option<Timer>,
+    /// Radius from before the cut, restored when it ends.
+    restore_radius: f32,
+}
+
+impl ActiveCameraCut {
+    pub fn is_active(&self) -> bool {
+        self.hold.is_some()
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (start_cut, end_cut).chain().in_set(CameraSystems::Cuts),
+    );
+}
+
+/// Run condition for camera systems that have to stay out of the way of a cut.
+pub fn no_camera_cut(cut: Res<ActiveCameraCut>) -> bool {
+    !cut.is_active()
+}
+
+fn start_cut(
+    mut cuts: EventReader<CameraCut>,
+    mut active: ResMut<ActiveCameraCut>,
+    mut pan_orbit: Single<&mut PanOrbitCamera, With<MainCamera>>,
+) {
+    let Some(cut) = cuts.read().last() else {
+        return;
+    };
+    if !active.is_active() {
+        active.restore_radius = pan_orbit.target_radius;
+    }
+    active.hold = Some(Timer::from_seconds(cut.hold_secs.max(0.0), TimerMode::Once));
+    // Set the current values too so it is a cut and not a pan.
+    pan_orbit.focus = cut.focus;
+    pan_orbit.target_focus = cut.focus;
+    if let Some(radius) = cut.radius {
+        pan_orbit.radius = Some(radius);
+        pan_orbit.target_radius = radius;
+    }
+    pan_orbit.enabled = false;
+    pan_orbit.force_update = true;
+}
+
+fn end_cut(
+    time: Res<Time<Real>>,
+    mut active: ResMut<ActiveCameraCut>,
+    mut mode: ResMut<CameraMode>,
+    mut pan_orbit: Single<&mut PanOrbitCamera, With<MainCamera>>,
+) {
+    let Some(hold) = active.hold.as_mut() else {
+        return;
+    };
+    if !hold.tick(time.delta()).finished() {
+        return;
+    }
+    active.hold = None;
+    pan_orbit.target_radius = active.restore_radius;
+    pan_orbit.force_update = true;
+    // Lets the mode take the camera back, including whether it takes input.
+    mode.set_changed();
+}
@@ -1,9 +1,20 @@
+pub mod cut;
+pub mod mode;
+pub mod shake;
+pub mod target;
+
+use crate::game::pause_controller::UiSystems;
 use bevy::core_pipeline::bloom::Bloom;
 use bevy::pbr::ShadowFilteringMethod;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 use bevy_panorbit_camera::PanOrbitCamera;
 use bevy_panorbit_camera::PanOrbitCameraPlugin;
+use bevy_panorbit_camera::PanOrbitCameraSystemSet;
+use cut::no_camera_cut;
+use mode::CameraMode;
+use shake::ScreenShake;
+use target::CameraTargetFocus;
 
 #[auto_register_type]
 #[auto_name]
@@ -11,19 +22,39 @@ use bevy_panorbit_camera::PanOrbitCameraPlugin;
 #[reflect(Component)]
 #[require(PanOrbitCamera)]
 #[require(ShadowFilteringMethod::Hardware2x2)]
+#[require(ScreenShake)]
 pub struct MainCamera;
 
-#[auto_register_type]
-#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
-#[reflect(Component)]
-#[require(Transform)]
-pub struct CameraTarget;
+/// Order of the camera systems, all of them run before [`PanOrbitCamera`] updates.
+#[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum CameraSystems {
+    Targets,
+    Cuts,
+    Modes,
+}
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
     app.add_plugins(PanOrbitCameraPlugin);
+    app.configure_sets(
+        Update,
+        (
+            CameraSystems::Targets,
+            CameraSystems::Cuts,
+            CameraSystems::Modes,
+        )
+            .chain()
+            .in_set(UiSystems)
+            .before(PanOrbitCameraSystemSet),
+    );
+    app.add_plugins((target::plugin, cut::plugin, mode::plugin, shake::plugin));
     app.add_systems(Startup, spawn_camera);
-    app.add_systems(Update, update_camera_target);
+    app.add_systems(
+        Update,
+        update_camera_target
+            .run_if(resource_equals(CameraMode::FreeOrbit).and(no_camera_cut))
+            .in_set(CameraSystems::Modes),
+    );
     app.insert_resource(AmbientLight {
         color: Color::WHITE,
         brightness: 2000.0,
@@ -49,19 +80,16 @@ fn spawn_camera(mut commands: Commands) {
 }
 
 fn update_camera_target(
-    mut pan_orbit_q: Single<Mut<PanOrbitCamera>>,
-    target_q: Single<
-        &GlobalTransform,
-        Or<(
-            Added<CameraTarget>,
-            (With<CameraTarget>, Changed<GlobalTransform>),
-        )>,
-    >,
+    mut pan_orbit_q: Single<Mut<PanOrbitCamera>, With<MainCamera>>,
+    target_focus: Res<CameraTargetFocus>,
 ) {
-    if pan_orbit_q.focus == target_q.translation() {
+    let Some(focus) = target_focus.focus else {
+        return;
+    };
+    if pan_orbit_q.target_focus == focus {
         return;
     }
-    pan_orbit_q.target_focus = target_q.translation();
+    pan_orbit_q.target_focus = focus;
     // Whenever changing properties manually like this, it's necessary to force
     // PanOrbitCamera to update this frame (by default it only updates when there are
     // input events).
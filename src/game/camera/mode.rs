@@ -0,0 +1,160 @@
+//! Switchable camera behaviors, cycled with C during gameplay.
+
+use crate::game::camera::cut::no_camera_cut;
+use crate::game::camera::target::{CameraTarget, CameraTargetFocus};
+use crate::game::camera::{CameraSystems, MainCamera};
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::prefabs::enemy_pool::InPlay;
+use crate::game::screens::Screen;
+use crate::game::spark::{Spark, Zapping};
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use bevy_panorbit_camera::PanOrbitCamera;
+use smart_default::SmartDefault;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub enum CameraMode {
+    /// Orbit freely around the [`CameraTarget`].
+    #[default]
+    FreeOrbit,
+    /// Keep the active [`Spark`] in focus.
+    FollowSpark,
+    /// Locked top-down view framing every enemy.
+    Tactical,
+}
+
+impl CameraMode {
+    fn next(self) -> Self {
+        match self {
+            Self::FreeOrbit => Self::FollowSpark,
+            Self::FollowSpark => Self::Tactical,
+            Self::Tactical => Self::FreeOrbit,
+        }
+    }
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct CameraModeConfig {
+    /// How quickly the camera catches up with the spark, higher is snappier.
+    #[default(5.0)]
+    pub follow_smoothing: f32,
+    /// Pitch of the tactical view, just short of straight down so the yaw stays stable.
+    #[default(89f32.to_radians())]
+    pub tactical_pitch: f32,
+    /// Extra room around the enemies in the tactical view, relative to their extent.
+    #[default(1.2)]
+    pub tactical_margin: f32,
+    /// Closest the tactical view zooms in.
+    #[default(200.0)]
+    pub tactical_min_radius: f32,
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            cycle_camera_mode.run_if(input_just_pressed(KeyCode::KeyC)),
+            (
+                apply_camera_mode.run_if(resource_changed::<CameraMode>),
+                follow_spark.run_if(resource_equals(CameraMode::FollowSpark)),
+                frame_enemies.run_if(resource_equals(CameraMode::Tactical)),
+            )
+                .run_if(no_camera_cut),
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(CameraSystems::Modes),
+    );
+}
+
+fn cycle_camera_mode(mut mode: ResMut<CameraMode>) {
+    *mode = mode.next();
+    info!("camera mode: {:?}", *mode);
+}
+
+fn apply_camera_mode(
+    mode: Res<CameraMode>,
+    mut pan_orbit: Single<&mut PanOrbitCamera, With<MainCamera>>,
+) {
+    // The tactical view is fully automatic.
+    pan_orbit.enabled = *mode != CameraMode::Tactical;
+    pan_orbit.force_update = true;
+}
+
+fn follow_spark(
+    time: Res<Time<Real>>,
+    config: Res<CameraModeConfig>,
+    mut pan_orbit: Single<&mut PanOrbitCamera, With<MainCamera>>,
+    sparks: Query<(&GlobalTransform, Has<Zapping>), With<Spark>>,
+    target_focus: Res<CameraTargetFocus>,
+) {
+    // Prefer a spark that is zapping something, fall back to the target once all are gone.
+    let spark = sparks
+        .iter()
+        .max_by_key(|(_, zapping)| *zapping)
+        .map(|(transform, _)| transform.translation());
+    let Some(focus) = spark.or(target_focus.focus) else {
+        return;
+    };
+    let t = 1.0 - (-config.follow_smoothing * time.delta_secs()).exp();
+    let smoothed = pan_orbit.target_focus.lerp(focus, t);
+    if pan_orbit.target_focus == smoothed {
+        return;
+    }
+    pan_orbit.target_focus = smoothed;
+    pan_orbit.force_update = true;
+}
+
+fn frame_enemies(
+    config: Res<CameraModeConfig>,
+    camera: Single<(&mut PanOrbitCamera, &Projection), With<MainCamera>>,
+    enemies: Query<&GlobalTransform, (With<Enemy>, InPlay)>,
+    targets: Query<&GlobalTransform, With<CameraTarget>>,
+) {
+    let (mut pan_orbit, projection) = camera.into_inner();
+    // Keep what is being defended in view as well.
+    let points = enemies
+        .iter()
+        .chain(targets.iter())
+        .map(GlobalTransform::translation);
+    let (min, max, count) = points.fold((Vec3::MAX, Vec3::MIN, 0), |(min, max, count), point| {
+        (min.min(point), max.max(point), count + 1)
+    });
+    if count == 0 {
+        return;
+    }
+    let center = (min + max) / 2.0;
+    let half_extent = (max - min).xz() / 2.0 * config.tactical_margin;
+    let (half_fov_y, aspect_ratio) = match projection {
+        Projection::Perspective(perspective) => (perspective.fov / 2.0, perspective.aspect_ratio),
+        _ => (std::f32::consts::FRAC_PI_4 / 2.0, 1.0),
+    };
+    // Looking down with no yaw, world X runs across the screen and world Z up and down it.
+    let half_fov_x = (half_fov_y.tan() * aspect_ratio).atan();
+    let radius = (half_extent.x / half_fov_x.tan())
+        .max(half_extent.y / half_fov_y.tan())
+        .max(config.tactical_min_radius)
+        // The extent is measured at the focus height, not at the top of the enemies.
+        + (max.y - center.y);
+
+    if pan_orbit.target_focus == center
+        && pan_orbit.target_radius == radius
+        && pan_orbit.target_yaw == 0.0
+        && pan_orbit.target_pitch == config.tactical_pitch
+    {
+        return;
+    }
+    pan_orbit.target_focus = center;
+    pan_orbit.target_radius = radius;
+    pan_orbit.target_yaw = 0.0;
+    pan_orbit.target_pitch = config.tactical_pitch;
+    pan_orbit.force_update = true;
+}
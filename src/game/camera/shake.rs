@@ -0,0 +1,69 @@
+//! Trauma based screen shake.
+//!
+//! Events add trauma that decays over time, the shake strength follows its square so small hits
+//! stay subtle while big ones stack up into a proper jolt.
+
+use crate::game::camera::CameraSystems;
+use crate::game::pause_controller::CosmeticSystems;
+use crate::game::rng::sphere::sample_point_in_ball;
+use crate::game::rng::stream::CosmeticRng;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraSystemSet};
+use smart_default::SmartDefault;
+
+/// Adds trauma to every [`ScreenShake`], clamped to 1.
+#[auto_register_type]
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct AddTrauma(pub f32);
+
+#[auto_register_type]
+#[derive(Component, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Component)]
+#[require(CosmeticRng)]
+pub struct ScreenShake {
+    /// Current shake amount between 0 and 1.
+    pub trauma: f32,
+    #[default(1.5)]
+    pub decay_per_second: f32,
+    /// How far the camera focus is pushed around at full trauma.
+    #[default(6.0)]
+    pub max_offset: f32,
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (add_trauma, shake)
+            .chain()
+            .in_set(CosmeticSystems)
+            .after(CameraSystems::Modes)
+            .before(PanOrbitCameraSystemSet),
+    );
+}
+
+fn add_trauma(mut events: EventReader<AddTrauma>, mut shakes: Query<&mut ScreenShake>) {
+    for AddTrauma(amount) in events.read() {
+        for mut shake in shakes.iter_mut() {
+            shake.trauma = (shake.trauma + amount).clamp(0.0, 1.0);
+        }
+    }
+}
+
+fn shake(
+    time: Res<Time>,
+    mut cameras: Query<(&mut ScreenShake, &mut CosmeticRng, &mut PanOrbitCamera)>,
+) {
+    for (mut shake, mut rng, mut pan_orbit) in cameras.iter_mut() {
+        if shake.trauma <= 0.0 {
+            continue;
+        }
+        // Only the current focus is nudged, PanOrbitCamera eases it back to the target focus.
+        let strength = shake.trauma * shake.trauma;
+        pan_orbit.focus += sample_point_in_ball(&mut **rng) * shake.max_offset * strength;
+        pan_orbit.force_update = true;
+        shake.trauma = (shake.trauma - shake.decay_per_second * time.delta_secs()).max(0.0);
+    }
+}
@@ -0,0 +1,82 @@
+//! What the camera looks at when nothing more specific is going on.
+
+use crate::game::camera::CameraSystems;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+pub const DEFAULT_CAMERA_TARGET_BLEND_SECS: f32 = 1.0;
+
+/// Entity the camera frames. With several targets the focus is their centroid, weighted by
+/// [`CameraTarget::weight`].
+#[auto_register_type]
+#[derive(Component, Debug, SmartDefault, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct CameraTarget {
+    /// Pull on the focus relative to the other targets, zero or less is ignored.
+    #[default(1.0)]
+    pub weight: f32,
+}
+
+/// Focus derived from the [`CameraTarget`]s, eased over whenever targets come, go or change
+/// weight so the camera doesn't snap.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct CameraTargetFocus {
+    /// `None` until there was a target to look at, afterwards the last focus is kept.
+    pub focus: Option<Vec3>,
+    blend_from: Option<Vec3>,
+    #[default(Timer::from_seconds(DEFAULT_CAMERA_TARGET_BLEND_SECS, TimerMode::Once))]
+    blend: Timer,
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(Update, update_focus.in_set(CameraSystems::Targets));
+}
+
+fn weighted_centroid<'a>(
+    targets: impl Iterator<Item = (&'a GlobalTransform, &'a CameraTarget)>,
+) -> Option<Vec3> {
+    let (sum, total_weight) = targets.filter(|(_, target)| target.weight > 0.0).fold(
+        (Vec3::ZERO, 0.0),
+        |(sum, total), (transform, target)| {
+            (
+                sum + transform.translation() * target.weight,
+                total + target.weight,
+            )
+        },
+    );
+    (total_weight > 0.0).then(|| sum / total_weight)
+}
+
+fn update_focus(
+    time: Res<Time<Real>>,
+    mut focus: ResMut<CameraTargetFocus>,
+    targets: Query<(&GlobalTransform, &CameraTarget)>,
+    changed: Query<(), Changed<CameraTarget>>,
+    mut removed: RemovedComponents<CameraTarget>,
+) {
+    let set_changed = !changed.is_empty() || removed.read().count() > 0;
+    let Some(centroid) = weighted_centroid(targets.iter()) else {
+        return;
+    };
+    if set_changed {
+        focus.blend_from = focus.focus;
+        focus.blend.reset();
+    }
+    focus.blend.tick(time.delta());
+    let next = match focus.blend_from {
+        Some(from) if !focus.blend.finished() => {
+            let t = focus.blend.fraction();
+            from.lerp(centroid, t * t * (3.0 - 2.0 * t))
+        }
+        _ => centroid,
+    };
+    if focus.focus != Some(next) {
+        focus.focus = Some(next);
+    }
+}
@@ -0,0 +1,108 @@
+//! Shows the running behavior tree node above debug selected enemies.
+
+use crate::game::behaviors::brain::Brain;
+use crate::game::camera::MainCamera;
+use crate::game::dev::selection::DebugSelected;
+use crate::game::pause_controller::UiSystems;
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+/// Label following the enemy that owns `brain`.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+struct BrainLabel {
+    brain: Entity,
+}
+
+/// How far above the enemy origin the label sits.
+const LABEL_HEIGHT: f32 = 40.0;
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (sync_brain_labels, update_brain_labels)
+            .chain()
+            .in_set(UiSystems),
+    );
+}
+
+/// Selection usually hits a mesh of the enemy, so the brain may sit on an ancestor.
+fn selected_brains(
+    selected: &Query<Entity, With<DebugSelected>>,
+    parents: &Query<&ChildOf>,
+    brains: &Query<(&Brain, &GlobalTransform)>,
+) -> Vec<Entity> {
+    let mut found = selected
+        .iter()
+        .filter_map(|entity| {
+            std::iter::once(entity)
+                .chain(parents.iter_ancestors(entity))
+                .find(|&entity| brains.contains(entity))
+        })
+        .collect::<Vec<_>>();
+    found.sort();
+    found.dedup();
+    found
+}
+
+fn sync_brain_labels(
+    mut commands: Commands,
+    selected: Query<Entity, With<DebugSelected>>,
+    parents: Query<&ChildOf>,
+    brains: Query<(&Brain, &GlobalTransform)>,
+    labels: Query<(Entity, &BrainLabel)>,
+) {
+    let wanted = selected_brains(&selected, &parents, &brains);
+    for (label, BrainLabel { brain }) in labels.iter() {
+        if !wanted.contains(brain) {
+            commands.entity(label).despawn();
+        }
+    }
+    for brain in wanted {
+        if labels.iter().any(|(_, label)| label.brain == brain) {
+            continue;
+        }
+        commands.spawn((
+            Name::new("Brain Label"),
+            BrainLabel { brain },
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Text::default(),
+            TextFont::from_font_size(14.0),
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+fn update_brain_labels(
+    camera: Option<Single<(&Camera, &GlobalTransform), With<MainCamera>>>,
+    brains: Query<(&Brain, &GlobalTransform)>,
+    mut labels: Query<(&BrainLabel, &mut Node, &mut Text, &mut Visibility)>,
+) {
+    let Some(camera) = camera else {
+        return;
+    };
+    let (camera, camera_transform) = *camera;
+    for (label, mut node, mut text, mut visibility) in labels.iter_mut() {
+        let Ok((brain, transform)) = brains.get(label.brain) else {
+            continue;
+        };
+        let position = transform.translation() + Vec3::Y * LABEL_HEIGHT;
+        let Ok(viewport) = camera.world_to_viewport(camera_transform, position) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+        node.left = Px(viewport.x);
+        node.top = Px(viewport.y);
+        if text.0 != brain.active_node {
+            text.0.clone_from(&brain.active_node);
+        }
+    }
+}
@@ -0,0 +1,735 @@
+//! In-game level editor.
+//!
+//! Toggle with `F2`. While enabled the game is paused and the debug selection is used to pick
+//! level objects, the inspector can be used to edit their reflected fields.
+//!
+//! - `1` / `2` / `3` / `4`: place a spawner, conduit, obstacle or tower at the cursor
+//! - drag the gizmo handles of the selection: the arrows move along an axis, the square moves
+//!   across the ground and the ring rotates around the Y axis
+//! - hold `G`: move the selection with the cursor
+//! - hold `R`: rotate the selection around the Y axis with the mouse
+//! - `Delete`: remove the selection
+//! - `Ctrl + S`: export the level back to the file it was loaded from
+//!
+//! Spawners and obstacles stay on the terrain, so they get no Y arrow. The handles are drawn with
+//! gizmos and picked in screen space, the debug selection ignores the click that ends a drag.
+
+use crate::game::behaviors::target_select::DefenseTarget;
+use crate::game::camera::MainCamera;
+use crate::game::dev::selection::{DebugSelectEnabled, DebugSelected};
+use crate::game::effects::lightning_ball::{LightningBall, LightningBallConduit};
+use crate::game::menus::Menu;
+use crate::game::pause_controller::Pause;
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::prefabs::obstacle::Obstacle;
+use crate::game::prefabs::spawner::Spawner;
+use crate::game::prefabs::tower::Tower;
+use crate::game::scenes::game::LevelRoot;
+use crate::game::scenes::level::{
+    ConduitDefinition, CurrentLevel, LevelDefinition, Levels, ObstacleDefinition, SpawnerDefinition,
+};
+use crate::game::screens::Screen;
+use crate::game::terrain::heightmap::Heightmap;
+use avian3d::prelude::Collider;
+use bevy::color::palettes::css::{BLUE, FUCHSIA, LIME, RED, WHITE, YELLOW};
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::input::mouse::AccumulatedMouseMotion;
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_auto_plugin::auto_plugin::*;
+use std::time::Duration;
+
+/// Height of a spawner's center above the terrain.
+const SPAWNER_HEIGHT: f32 = 5.0;
+const CONDUIT_HEIGHT: f32 = 30.0;
+const CONDUIT_RADIUS: f32 = 5.0;
+const OBSTACLE_SIZE: Vec3 = Vec3::new(40.0, 20.0, 40.0);
+/// Height of a tower's center, towers stand in clearings flattened to the path height.
+const TOWER_HEIGHT: f32 = 50.0;
+const ROTATE_SENSITIVITY: f32 = 0.01;
+const HANDLE_LENGTH: f32 = 30.0;
+const PLANE_HANDLE_SIZE: f32 = 10.0;
+const ROTATE_RING_RADIUS: f32 = 40.0;
+const ROTATE_RING_SEGMENTS: usize = 32;
+/// How close in pixels the cursor has to be to a handle to grab it.
+const HANDLE_PICK_RADIUS: f32 = 8.0;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct LevelEditorEnabled(pub bool);
+
+/// Objects that are kept on the terrain when moved.
+type GroundedFilter = Or<(With<Spawner>, With<Obstacle>)>;
+
+/// Part of the transform gizmo drawn on selected level objects.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect)]
+enum GizmoHandle {
+    MoveX,
+    MoveY,
+    MoveZ,
+    /// Moves across the ground plane.
+    MovePlane,
+    RotateY,
+}
+
+impl GizmoHandle {
+    const ALL: [Self; 5] = [
+        Self::MoveX,
+        Self::MoveY,
+        Self::MoveZ,
+        Self::MovePlane,
+        Self::RotateY,
+    ];
+
+    fn axis(self) -> Option<Vec3> {
+        match self {
+            Self::MoveX => Some(Vec3::X),
+            Self::MoveY => Some(Vec3::Y),
+            Self::MoveZ => Some(Vec3::Z),
+            Self::MovePlane | Self::RotateY => None,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Self::MoveX => RED.into(),
+            Self::MoveY => LIME.into(),
+            Self::MoveZ => BLUE.into(),
+            Self::MovePlane => WHITE.into(),
+            Self::RotateY => FUCHSIA.into(),
+        }
+    }
+
+    /// Handles of an object, grounded objects follow the terrain and can't be moved up.
+    fn of(grounded: bool) -> impl Iterator<Item = Self> {
+        Self::ALL
+            .into_iter()
+            .filter(move |&handle| !(grounded && handle == Self::MoveY))
+    }
+
+    /// The handle as a line strip for an object at `origin`.
+    fn points(self, origin: Vec3) -> Vec<Vec3> {
+        match self {
+            Self::MoveX | Self::MoveY | Self::MoveZ => {
+                vec![
+                    origin,
+                    origin + self.axis().unwrap_or_default() * HANDLE_LENGTH,
+                ]
+            }
+            Self::MovePlane => [
+                Vec2::new(-1.0, -1.0),
+                Vec2::new(1.0, -1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(-1.0, 1.0),
+                Vec2::new(-1.0, -1.0),
+            ]
+            .map(|corner| origin + (corner * PLANE_HANDLE_SIZE / 2.0).extend(0.0).xzy())
+            .to_vec(),
+            Self::RotateY => (0..=ROTATE_RING_SEGMENTS)
+                .map(|segment| {
+                    let angle =
+                        segment as f32 / ROTATE_RING_SEGMENTS as f32 * std::f32::consts::TAU;
+                    origin + Vec3::new(angle.cos(), 0.0, angle.sin()) * ROTATE_RING_RADIUS
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A handle being dragged, with where the selection was when it was grabbed.
+#[derive(Debug, Clone, Reflect)]
+struct ActiveDrag {
+    entity: Entity,
+    handle: GizmoHandle,
+    /// Origin of the grabbed object.
+    pivot: Vec3,
+    /// Where the cursor was on the handle's constraint when grabbed.
+    grab: Vec3,
+    start: Vec<(Entity, Transform)>,
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Clone, Reflect)]
+#[reflect(Resource)]
+struct HandleDrag(Option<ActiveDrag>);
+
+type EditableFilter = Or<(
+    With<Spawner>,
+    With<Tower>,
+    With<LightningBall>,
+    With<LightningBallConduit>,
+    With<Obstacle>,
+)>;
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            toggle_editor.run_if(input_just_pressed(KeyCode::F2)),
+            // Closing a menu unpauses, keep the game paused for as long as the editor is open.
+            pause
+                .run_if(|enabled: Res<LevelEditorEnabled>| enabled.0)
+                .run_if(in_state(Pause(false))),
+            (
+                place,
+                drag_handles,
+                grab,
+                rotate,
+                delete.run_if(input_just_pressed(KeyCode::Delete)),
+                export.run_if(input_just_pressed(KeyCode::KeyS)),
+                draw_selection_gizmos,
+            )
+                .run_if(|enabled: Res<LevelEditorEnabled>| enabled.0),
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), disable_editor);
+}
+
+fn toggle_editor(
+    mut enabled: ResMut<LevelEditorEnabled>,
+    mut debug_select_enabled: ResMut<DebugSelectEnabled>,
+    menu: Res<State<Menu>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut drag: ResMut<HandleDrag>,
+) {
+    enabled.0 = !enabled.0;
+    debug_select_enabled.0 = enabled.0;
+    drag.0 = None;
+    // Keep spawners and enemies from changing the level while it's being edited, an open menu
+    // keeps the game paused after.
+    next_pause.set(Pause(enabled.0 || *menu.get() != Menu::None));
+}
+
+fn pause(mut next_pause: ResMut<NextState<Pause>>) {
+    next_pause.set(Pause(true));
+}
+
+fn disable_editor(
+    mut enabled: ResMut<LevelEditorEnabled>,
+    mut debug_select_enabled: ResMut<DebugSelectEnabled>,
+    mut drag: ResMut<HandleDrag>,
+) {
+    // Leaving gameplay unpauses on its own.
+    enabled.0 = false;
+    // A drag cut short has the selection turned off.
+    if drag.0.take().is_some() {
+        debug_select_enabled.0 = true;
+    }
+}
+
+/// Point on the ground plane under the cursor.
+fn cursor_ground_point(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<Vec3> {
+    let cursor = window.cursor_position()?;
+    let ray = camera.viewport_to_world(camera_transform, cursor).ok()?;
+    let distance = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Y))?;
+    Some(ray.get_point(distance))
+}
+
+fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let t =
+        ((point - start).dot(segment) / segment.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+    point.distance(start + segment * t)
+}
+
+/// Distance along `axis` (normalized) from `origin` to the point of that line closest to `ray`,
+/// `None` when they're close to parallel.
+fn closest_on_axis(ray: Ray3d, origin: Vec3, axis: Vec3) -> Option<f32> {
+    let to_ray = ray.origin - origin;
+    let alignment = axis.dot(*ray.direction);
+    let denominator = 1.0 - alignment * alignment;
+    if denominator < 1e-4 {
+        return None;
+    }
+    Some((axis.dot(to_ray) - alignment * ray.direction.dot(to_ray)) / denominator)
+}
+
+/// Where `ray` meets what `handle` is constrained to, an axis or the ground plane through `pivot`.
+fn drag_point(handle: GizmoHandle, pivot: Vec3, ray: Ray3d) -> Option<Vec3> {
+    match handle.axis() {
+        Some(axis) => closest_on_axis(ray, pivot, axis).map(|distance| pivot + axis * distance),
+        None => {
+            let distance = ray.intersect_plane(pivot, InfinitePlane3d::new(Vec3::Y))?;
+            Some(ray.get_point(distance))
+        }
+    }
+}
+
+/// Angle around the Y axis of `point` seen from `pivot`, matching [`Quat::from_rotation_y`].
+fn yaw_around(pivot: Vec3, point: Vec3) -> f32 {
+    let offset = point - pivot;
+    (-offset.z).atan2(offset.x)
+}
+
+/// Handle closest to the cursor within [`HANDLE_PICK_RADIUS`] and the object it belongs to.
+fn handle_under_cursor(
+    cursor: Vec2,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    objects: impl IntoIterator<Item = (Entity, Vec3, bool)>,
+) -> Option<(Entity, GizmoHandle)> {
+    objects
+        .into_iter()
+        .flat_map(|(entity, origin, grounded)| {
+            GizmoHandle::of(grounded).map(move |handle| (entity, origin, handle))
+        })
+        .filter_map(|(entity, origin, handle)| {
+            let points = handle
+                .points(origin)
+                .into_iter()
+                .map(|point| camera.world_to_viewport(camera_transform, point).ok())
+                .collect::<Option<Vec<_>>>()?;
+            let distance = points
+                .windows(2)
+                .map(|segment| distance_to_segment(cursor, segment[0], segment[1]))
+                .fold(f32::INFINITY, f32::min);
+            (distance <= HANDLE_PICK_RADIUS).then_some((entity, handle, distance))
+        })
+        .min_by(|(.., a), (.., b)| a.total_cmp(b))
+        .map(|(entity, handle, _)| (entity, handle))
+}
+
+fn terrain_height(heightmap: Option<&Heightmap>, xz: Vec2) -> f32 {
+    heightmap.map_or(0.0, |heightmap| heightmap.height_at(xz))
+}
+
+/// Level objects owning the selected entities, picking usually hits a mesh child.
+fn selected_roots(
+    selected: &Query<Entity, With<DebugSelected>>,
+    parents: &Query<&ChildOf>,
+    level_root: Entity,
+    editable: &Query<(), EditableFilter>,
+) -> HashSet<Entity> {
+    selected
+        .iter()
+        .filter_map(|entity| {
+            std::iter::once(entity)
+                .chain(parents.iter_ancestors(entity))
+                .find(|&ancestor| {
+                    editable.contains(ancestor)
+                        && parents
+                            .get(ancestor)
+                            .is_ok_and(|child_of| child_of.parent() == level_root)
+                })
+        })
+        .collect()
+}
+
+fn place(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<MainCamera>>,
+    level_root: Single<Entity, With<LevelRoot>>,
+    heightmap: Option<Res<Heightmap>>,
+) {
+    let (camera, camera_transform) = *camera;
+    let Some(point) = cursor_ground_point(&window, camera, camera_transform) else {
+        return;
+    };
+    let ground = terrain_height(heightmap.as_deref(), point.xz());
+    if input.just_pressed(KeyCode::Digit1) {
+        commands.entity(*level_root).with_child((
+            Spawner::new(
+                Enemy::BaseSkele,
+                Vec::new(),
+                Duration::from_secs_f32(4.0),
+                Duration::ZERO,
+                4,
+            ),
+            Transform::from_translation(point.with_y(ground + SPAWNER_HEIGHT)),
+        ));
+    }
+    if input.just_pressed(KeyCode::Digit2) {
+        commands.entity(*level_root).with_child((
+            Name::new("Conduit"),
+            LightningBallConduit,
+            DefenseTarget::new(0.5),
+            Transform::from_translation(point.with_y(ground + CONDUIT_HEIGHT)),
+            Collider::sphere(CONDUIT_RADIUS),
+        ));
+    }
+    if input.just_pressed(KeyCode::Digit3) {
+        commands.entity(*level_root).with_child((
+            Obstacle {
+                size: OBSTACLE_SIZE,
+            },
+            Transform::from_translation(point.with_y(ground + OBSTACLE_SIZE.y / 2.0)),
+        ));
+    }
+    if input.just_pressed(KeyCode::Digit4) {
+        commands.entity(*level_root).with_child((
+            Tower,
+            Transform::from_translation(point.with_y(TOWER_HEIGHT)),
+        ));
+    }
+}
+
+fn drag_handles(
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<MainCamera>>,
+    level_root: Single<Entity, With<LevelRoot>>,
+    selected: Query<Entity, With<DebugSelected>>,
+    parents: Query<&ChildOf>,
+    editable: Query<(), EditableFilter>,
+    grounded: Query<(), GroundedFilter>,
+    heightmap: Option<Res<Heightmap>>,
+    global_transforms: Query<&GlobalTransform>,
+    mut drag: ResMut<HandleDrag>,
+    mut debug_select_enabled: ResMut<DebugSelectEnabled>,
+    mut transforms: Query<&mut Transform>,
+) {
+    let (camera, camera_transform) = *camera;
+    let ray = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor).ok());
+
+    if mouse.just_pressed(MouseButton::Left) {
+        drag.0 = None;
+        debug_select_enabled.0 = true;
+        let roots = selected_roots(&selected, &parents, *level_root, &editable);
+        let objects = roots.iter().filter_map(|&entity| {
+            let global_transform = global_transforms.get(entity).ok()?;
+            Some((
+                entity,
+                global_transform.translation(),
+                grounded.contains(entity),
+            ))
+        });
+        let Some((entity, handle)) = window
+            .cursor_position()
+            .and_then(|cursor| handle_under_cursor(cursor, camera, camera_transform, objects))
+        else {
+            return;
+        };
+        let Ok(pivot) = global_transforms
+            .get(entity)
+            .map(GlobalTransform::translation)
+        else {
+            return;
+        };
+        let Some(grab) = ray.and_then(|ray| drag_point(handle, pivot, ray)) else {
+            return;
+        };
+        drag.0 = Some(ActiveDrag {
+            entity,
+            handle,
+            pivot,
+            grab,
+            start: roots
+                .iter()
+                .filter_map(|&entity| Some((entity, *transforms.get(entity).ok()?)))
+                .collect(),
+        });
+        // The release ending the drag would otherwise select whatever is under the cursor.
+        debug_select_enabled.0 = false;
+    }
+
+    if drag.0.is_some() && !mouse.pressed(MouseButton::Left) {
+        // Selecting runs on release, so only hand the mouse back a frame later.
+        if !mouse.just_released(MouseButton::Left) {
+            drag.0 = None;
+            debug_select_enabled.0 = true;
+        }
+        return;
+    }
+    let (Some(active), Some(ray)) = (&drag.0, ray) else {
+        return;
+    };
+    let Some(point) = drag_point(active.handle, active.pivot, ray) else {
+        return;
+    };
+    for &(entity, start) in &active.start {
+        let Ok(mut transform) = transforms.get_mut(entity) else {
+            continue;
+        };
+        if active.handle == GizmoHandle::RotateY {
+            let yaw = yaw_around(active.pivot, point) - yaw_around(active.pivot, active.grab);
+            transform.rotation = Quat::from_rotation_y(yaw) * start.rotation;
+            continue;
+        }
+        let mut translation = start.translation + point - active.grab;
+        if grounded.contains(entity) {
+            translation.y = start.translation.y
+                + terrain_height(heightmap.as_deref(), translation.xz())
+                - terrain_height(heightmap.as_deref(), start.translation.xz());
+        }
+        transform.translation = translation;
+    }
+}
+
+fn grab(
+    input: Res<ButtonInput<KeyCode>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<MainCamera>>,
+    level_root: Single<Entity, With<LevelRoot>>,
+    selected: Query<Entity, With<DebugSelected>>,
+    parents: Query<&ChildOf>,
+    editable: Query<(), EditableFilter>,
+    grounded: Query<(), GroundedFilter>,
+    heightmap: Option<Res<Heightmap>>,
+    mut transforms: Query<&mut Transform>,
+) {
+    if !input.pressed(KeyCode::KeyG) {
+        return;
+    }
+    let (camera, camera_transform) = *camera;
+    let Some(point) = cursor_ground_point(&window, camera, camera_transform) else {
+        return;
+    };
+    for entity in selected_roots(&selected, &parents, *level_root, &editable) {
+        let Ok(mut transform) = transforms.get_mut(entity) else {
+            continue;
+        };
+        let mut y = transform.translation.y;
+        if grounded.contains(entity) {
+            y += terrain_height(heightmap.as_deref(), point.xz())
+                - terrain_height(heightmap.as_deref(), transform.translation.xz());
+        }
+        transform.translation = point.with_y(y);
+    }
+}
+
+fn rotate(
+    input: Res<ButtonInput<KeyCode>>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    level_root: Single<Entity, With<LevelRoot>>,
+    selected: Query<Entity, With<DebugSelected>>,
+    parents: Query<&ChildOf>,
+    editable: Query<(), EditableFilter>,
+    mut transforms: Query<&mut Transform>,
+) {
+    if !input.pressed(KeyCode::KeyR) || mouse_motion.delta.x == 0.0 {
+        return;
+    }
+    for entity in selected_roots(&selected, &parents, *level_root, &editable) {
+        let Ok(mut transform) = transforms.get_mut(entity) else {
+            continue;
+        };
+        transform.rotate_y(mouse_motion.delta.x * ROTATE_SENSITIVITY);
+    }
+}
+
+fn delete(
+    mut commands: Commands,
+    level_root: Single<Entity, With<LevelRoot>>,
+    selected: Query<Entity, With<DebugSelected>>,
+    parents: Query<&ChildOf>,
+    editable: Query<(), EditableFilter>,
+    lightning_ball: Query<(), With<LightningBall>>,
+    towers: Query<(), With<Tower>>,
+) {
+    let mut towers_left = towers.iter().count();
+    for entity in selected_roots(&selected, &parents, *level_root, &editable) {
+        // The level needs its lightning ball and at least one tower.
+        if lightning_ball.contains(entity) || (towers.contains(entity) && towers_left == 1) {
+            warn!("can't delete {entity}, the level requires it");
+            continue;
+        }
+        if towers.contains(entity) {
+            towers_left -= 1;
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+fn export(
+    input: Res<ButtonInput<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    current_level: Res<CurrentLevel>,
+    levels: Levels,
+    level_root: Single<Entity, With<LevelRoot>>,
+    lightning_ball: Single<&Transform, With<LightningBall>>,
+    towers: Query<(&Transform, &ChildOf), With<Tower>>,
+    spawners: Query<(&Spawner, &Transform, &ChildOf)>,
+    conduits: Query<(&Transform, &Collider, &ChildOf), With<LightningBallConduit>>,
+    obstacles: Query<(&Obstacle, &Transform, &ChildOf)>,
+    heightmap: Option<Res<Heightmap>>,
+) {
+    if !input.pressed(KeyCode::ControlLeft) && !input.pressed(KeyCode::ControlRight) {
+        return;
+    }
+    let (Some(current), Some(handle)) =
+        (levels.get(current_level.0), levels.handle(current_level.0))
+    else {
+        return;
+    };
+    let in_level = |child_of: &ChildOf| child_of.parent() == *level_root;
+    // Inverse of the terrain offset applied when the level is spawned.
+    let from_terrain = |translation: Vec3| {
+        translation
+            - Vec3::Y
+                * (terrain_height(heightmap.as_deref(), translation.xz())
+                    - current.ground_surface())
+    };
+
+    let level = LevelDefinition {
+        name: current.name.clone(),
+        ground_size: current.ground_size,
+        lightning_ball: lightning_ball.translation,
+        towers: towers
+            .iter()
+            .filter(|(_, child_of)| in_level(child_of))
+            .map(|(transform, _)| transform.translation)
+            .collect(),
+        spawners: spawners
+            .iter()
+            .filter(|(_, _, child_of)| in_level(child_of))
+            .map(|(spawner, transform, _)| SpawnerDefinition {
+                translation: from_terrain(transform.translation),
+                spawns: spawner.spawns,
+                mix: spawner.mix.clone(),
+                spawn_duration_secs: spawner.spawn_duration.as_secs_f32(),
+                // The authored values, the countdowns have been running since the level started.
+                initial_delay_secs: spawner.initial_delay.as_secs_f32(),
+                spawn_count: spawner.spawn_count,
+            })
+            .collect(),
+        conduits: conduits
+            .iter()
+            .filter(|(_, _, child_of)| in_level(child_of))
+            .filter_map(|(transform, collider, _)| {
+                let Some(ball) = collider.shape().as_ball() else {
+                    warn!("skipping conduit with a non sphere collider");
+                    return None;
+                };
+                Some(ConduitDefinition {
+                    translation: transform.translation,
+                    radius: ball.radius,
+                })
+            })
+            .collect(),
+        obstacles: obstacles
+            .iter()
+            .filter(|(_, _, child_of)| in_level(child_of))
+            .map(|(obstacle, transform, _)| ObstacleDefinition {
+                translation: from_terrain(transform.translation),
+                yaw_deg: transform.rotation.to_euler(EulerRot::YXZ).0.to_degrees(),
+                size: obstacle.size,
+            })
+            .collect(),
+    };
+
+    let Some(path) = asset_server.get_path(handle) else {
+        error!("level has no asset path to export to");
+        return;
+    };
+    let ron = match level.to_ron() {
+        Ok(ron) => ron,
+        Err(err) => {
+            error!("failed to serialize level: {err}");
+            return;
+        }
+    };
+    write_level(&format!("assets/{}", path.path().display()), &ron);
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write_level(path: &str, ron: &str) {
+    match std::fs::write(path, ron) {
+        Ok(()) => info!("exported level to {path}"),
+        Err(err) => error!("failed to export level to {path}: {err}"),
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn write_level(path: &str, ron: &str) {
+    warn!("can't write {path} on the web, level:\n{ron}");
+}
+
+fn draw_selection_gizmos(
+    mut gizmos: Gizmos,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<MainCamera>>,
+    drag: Res<HandleDrag>,
+    level_root: Single<Entity, With<LevelRoot>>,
+    selected: Query<Entity, With<DebugSelected>>,
+    parents: Query<&ChildOf>,
+    editable: Query<(), EditableFilter>,
+    grounded: Query<(), GroundedFilter>,
+    global_transforms: Query<&GlobalTransform>,
+) {
+    let (camera, camera_transform) = *camera;
+    let objects = selected_roots(&selected, &parents, *level_root, &editable)
+        .into_iter()
+        .filter_map(|entity| {
+            let global_transform = global_transforms.get(entity).ok()?;
+            Some((entity, *global_transform, grounded.contains(entity)))
+        })
+        .collect::<Vec<_>>();
+    let highlighted = match &drag.0 {
+        Some(active) => Some((active.entity, active.handle)),
+        None => window.cursor_position().and_then(|cursor| {
+            handle_under_cursor(
+                cursor,
+                camera,
+                camera_transform,
+                objects.iter().map(|&(entity, global_transform, grounded)| {
+                    (entity, global_transform.translation(), grounded)
+                }),
+            )
+        }),
+    };
+
+    for (entity, global_transform, grounded) in objects {
+        let origin = global_transform.translation();
+        for handle in GizmoHandle::of(grounded) {
+            let color = if highlighted == Some((entity, handle)) {
+                YELLOW.into()
+            } else {
+                handle.color()
+            };
+            let points = handle.points(origin);
+            if handle.axis().is_some() {
+                gizmos.arrow(points[0], points[1], color);
+            } else {
+                gizmos.linestrip(points, color);
+            }
+        }
+        // Shows the orientation while rotating.
+        gizmos.line(
+            origin,
+            origin + global_transform.forward() * ROTATE_RING_RADIUS,
+            GizmoHandle::RotateY.color(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closest_on_axis_under_a_downward_ray() {
+        let ray = Ray3d::new(Vec3::new(5.0, 10.0, 3.0), Dir3::NEG_Y);
+        let distance = closest_on_axis(ray, Vec3::new(1.0, 0.0, 0.0), Vec3::X).unwrap();
+        assert!((distance - 4.0).abs() < 1e-5);
+        assert_eq!(closest_on_axis(ray, Vec3::ZERO, Vec3::Y), None);
+    }
+
+    #[test]
+    fn yaw_around_matches_rotation() {
+        let yaw = 0.7;
+        let point = Quat::from_rotation_y(yaw) * Vec3::X * 10.0;
+        assert!((yaw_around(Vec3::ZERO, point) - yaw).abs() < 1e-5);
+    }
+
+    #[test]
+    fn distance_to_segment_clamps_to_the_ends() {
+        let (start, end) = (Vec2::ZERO, Vec2::new(10.0, 0.0));
+        assert_eq!(distance_to_segment(Vec2::new(5.0, 3.0), start, end), 3.0);
+        assert_eq!(distance_to_segment(Vec2::new(-4.0, 3.0), start, end), 5.0);
+    }
+}
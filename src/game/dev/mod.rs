@@ -1,3 +1,7 @@
+#[cfg(feature = "inspector_ui")]
+mod brain_debug;
+#[cfg(feature = "inspector_ui")]
+mod editor;
 mod fps;
 mod inspector_ui;
 mod selection;
@@ -13,6 +17,8 @@ pub(crate) fn plugin(app: &mut App) {
     {
         app.add_plugins(inspector_ui::plugin);
         app.add_plugins(selection::plugin);
+        app.add_plugins(brain_debug::plugin);
+        app.add_plugins(editor::plugin);
     }
     app.add_systems(
         Update,
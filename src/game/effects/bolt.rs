@@ -0,0 +1,343 @@
+//! Mesh based lightning bolt rendering.
+//!
+//! A [`Bolt`] is a set of world space polylines that get turned into an
+//! emissive tube mesh so they pick up bloom and have actual thickness.
+
+use crate::game::despawn::DespawnDelayed;
+use crate::game::effects::bolt_generator::{BoltPath, BoltTree};
+use crate::game::pause_controller::CosmeticSystems;
+use bevy::asset::RenderAssetUsages;
+use bevy::color::palettes::css::SKY_BLUE;
+use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+use std::f32::consts::TAU;
+
+/// A lightning bolt made of one or more world space polylines.
+/// The first path is the trunk, any following paths are branches.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform, Visibility, BoltStyle, NotShadowCaster, NotShadowReceiver)]
+#[require(Pickable = Pickable::IGNORE)]
+pub struct Bolt {
+    pub paths: Vec<BoltPath>,
+}
+
+impl Bolt {
+    pub fn new(trunk: Vec<Vec3>) -> Self {
+        Self {
+            paths: vec![BoltPath {
+                points: trunk,
+                depth: 0,
+            }],
+        }
+    }
+}
+
+impl From<BoltTree> for Bolt {
+    fn from(tree: BoltTree) -> Self {
+        Self { paths: tree.paths }
+    }
+}
+
+#[auto_register_type]
+#[derive(Component, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct BoltStyle {
+    /// Radius of the trunk at its start.
+    #[default(0.05)]
+    pub width: f32,
+    /// Radius multiplier applied for each level of branching.
+    #[default(0.5)]
+    pub branch_width_scale: f32,
+    /// How much the radius shrinks towards the end of a path (0 = none, 1 = to a point).
+    #[default(0.5)]
+    pub taper: f32,
+    /// Number of sides of the tube cross-section.
+    #[default(4)]
+    pub sides: u32,
+    #[default(Color::from(SKY_BLUE))]
+    pub color: Color,
+    /// Multiplier applied to `color` for the emissive channel, values above 1 bloom.
+    #[default(20.0)]
+    pub emissive_strength: f32,
+}
+
+/// Despawns the [`Bolt`] once the timer finishes.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct BoltLifetime(pub Timer);
+
+impl BoltLifetime {
+    pub fn from_secs(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
+/// Toggles the visibility of a [`Bolt`] on and off while it is alive.
+#[auto_register_type]
+#[derive(Component, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct BoltFlicker {
+    /// Flicker cycles per second.
+    #[default(30.0)]
+    pub frequency: f32,
+    /// Fraction of each cycle the bolt is visible.
+    #[default(0.7)]
+    pub duty: f32,
+    /// Offset into the cycle so bolts spawned together don't flicker in sync.
+    pub phase: f32,
+}
+
+/// Draws bolts with gizmos on top of the meshes.
+/// Only meant for debugging the generated paths.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct BoltDebugGizmosEnabled(pub bool);
+
+/// Materials shared by bolts with the same color and emissive strength.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct BoltMaterialCache(HashMap<([u8; 4], u32), Handle<StandardMaterial>>);
+
+impl BoltMaterialCache {
+    fn get_or_add(
+        &mut self,
+        materials: &mut Assets<StandardMaterial>,
+        style: &BoltStyle,
+    ) -> Handle<StandardMaterial> {
+        let key = (
+            style.color.to_srgba().to_u8_array(),
+            style.emissive_strength.to_bits(),
+        );
+        self.0
+            .entry(key)
+            .or_insert_with(|| {
+                let color = LinearRgba::from(style.color);
+                let strength = style.emissive_strength;
+                materials.add(StandardMaterial {
+                    base_color: style.color,
+                    emissive: LinearRgba::rgb(
+                        color.red * strength,
+                        color.green * strength,
+                        color.blue * strength,
+                    ),
+                    unlit: true,
+                    ..Default::default()
+                })
+            })
+            .clone()
+    }
+}
+
+/// Meshes of despawned bolts, overwritten by new bolts instead of adding a mesh asset for each.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct BoltMeshPool(Vec<Handle<Mesh>>);
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(Update, (tick_lifetime, flicker).in_set(CosmeticSystems));
+    // Build in PostUpdate so bolts spawned during Update are visible the same frame.
+    app.add_systems(PostUpdate, build_meshes);
+    app.add_observer(recycle_mesh);
+    #[cfg(feature = "dev")]
+    app.add_systems(
+        Update,
+        (
+            toggle_debug_gizmos,
+            draw_debug_gizmos.run_if(|res: Res<BoltDebugGizmosEnabled>| res.0),
+        )
+            .chain(),
+    );
+}
+
+fn build_meshes(
+    mut commands: Commands,
+    bolts: Query<
+        (Entity, &Bolt, &BoltStyle, Option<&Mesh3d>),
+        Or<(Changed<Bolt>, Changed<BoltStyle>)>,
+    >,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut material_cache: ResMut<BoltMaterialCache>,
+    mut mesh_pool: ResMut<BoltMeshPool>,
+) {
+    for (entity, bolt, style, mesh_3d) in bolts.iter() {
+        let mesh = bolt_mesh(bolt, style);
+        let reused = mesh_3d
+            .map(|mesh_3d| mesh_3d.0.clone())
+            .or_else(|| mesh_pool.0.pop())
+            .filter(|handle| meshes.contains(handle));
+        let handle = match reused {
+            Some(handle) => {
+                meshes.insert(&handle, mesh);
+                handle
+            }
+            None => meshes.add(mesh),
+        };
+        commands.entity(entity).insert((
+            Mesh3d(handle),
+            MeshMaterial3d(material_cache.get_or_add(&mut materials, style)),
+        ));
+    }
+}
+
+fn recycle_mesh(
+    trigger: Trigger<OnRemove, Mesh3d>,
+    bolts: Query<&Mesh3d, With<Bolt>>,
+    mut mesh_pool: ResMut<BoltMeshPool>,
+) {
+    if let Ok(mesh_3d) = bolts.get(trigger.target()) {
+        mesh_pool.0.push(mesh_3d.0.clone());
+    }
+}
+
+fn tick_lifetime(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bolts: Query<(Entity, &mut BoltLifetime)>,
+) {
+    for (entity, mut lifetime) in bolts.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(entity).trigger(DespawnDelayed);
+        }
+    }
+}
+
+fn flicker(
+    time: Res<Time>,
+    mut bolts: Query<(&BoltFlicker, Option<&BoltLifetime>, &mut Visibility), With<Bolt>>,
+) {
+    for (flicker, lifetime, mut visibility) in bolts.iter_mut() {
+        let elapsed = lifetime.map_or(time.elapsed(), |lifetime| lifetime.0.elapsed());
+        let cycle = (elapsed.as_secs_f32() * flicker.frequency + flicker.phase).fract();
+        let target = if cycle < flicker.duty {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(target);
+    }
+}
+
+#[cfg(feature = "dev")]
+fn toggle_debug_gizmos(
+    mut enabled: ResMut<BoltDebugGizmosEnabled>,
+    input: Res<ButtonInput<KeyCode>>,
+) {
+    if input.just_pressed(KeyCode::KeyB) {
+        enabled.0 = !enabled.0;
+    }
+}
+
+#[cfg(feature = "dev")]
+fn draw_debug_gizmos(mut gizmos: Gizmos, bolts: Query<(&Bolt, &BoltStyle)>) {
+    use itertools::Itertools;
+    for (bolt, style) in bolts.iter() {
+        for path in bolt.paths.iter() {
+            for (&a, &b) in path.points.iter().tuple_windows() {
+                gizmos.line_gradient(a, b, Color::WHITE, style.color);
+            }
+        }
+    }
+}
+
+/// Builds a tube mesh following every path of the bolt.
+pub fn bolt_mesh(bolt: &Bolt, style: &BoltStyle) -> Mesh {
+    let sides = style.sides.max(3);
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    for BoltPath {
+        points: path,
+        depth,
+    } in bolt.paths.iter()
+    {
+        if path.len() < 2 {
+            continue;
+        }
+        let width = style.width * style.branch_width_scale.powi(*depth as i32);
+        let base = positions.len() as u32;
+        let last_ix = path.len() - 1;
+
+        // Keep the cross-section basis stable along the path to avoid twisting.
+        let mut side = Vec3::ZERO;
+        for (ix, &point) in path.iter().enumerate() {
+            let tangent = if ix == 0 {
+                path[1] - path[0]
+            } else if ix == last_ix {
+                path[last_ix] - path[last_ix - 1]
+            } else {
+                path[ix + 1] - path[ix - 1]
+            }
+            .normalize_or(Vec3::Y);
+            let projected = side.reject_from_normalized(tangent);
+            side = if projected.length_squared() > f32::EPSILON {
+                projected.normalize()
+            } else {
+                tangent.any_orthonormal_vector()
+            };
+            let up = tangent.cross(side);
+
+            let t = ix as f32 / last_ix as f32;
+            let radius = width * (1.0 - style.taper * t);
+            for s in 0..sides {
+                let angle = s as f32 / sides as f32 * TAU;
+                let normal = side * angle.cos() + up * angle.sin();
+                positions.push((point + normal * radius).to_array());
+                normals.push(normal.to_array());
+            }
+        }
+
+        for ring in 0..last_ix as u32 {
+            let current = base + ring * sides;
+            let next = current + sides;
+            for s in 0..sides {
+                let s_next = (s + 1) % sides;
+                indices.extend_from_slice(&[
+                    current + s,
+                    next + s,
+                    current + s_next,
+                    current + s_next,
+                    next + s,
+                    next + s_next,
+                ]);
+            }
+        }
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Helper for spawning a short-lived flickering [`Bolt`].
+pub fn bolt(bolt: Bolt, style: BoltStyle, lifetime_secs: f32, phase: f32) -> impl Bundle {
+    (
+        Name::new("Bolt"),
+        bolt,
+        style,
+        BoltLifetime::from_secs(lifetime_secs.max(0.0)),
+        BoltFlicker {
+            phase,
+            ..Default::default()
+        },
+    )
+}
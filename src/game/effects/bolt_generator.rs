@@ -0,0 +1,298 @@
+//! Fractal lightning bolt generation.
+//!
+//! Bolts are built with midpoint displacement: the straight line between the
+//! two endpoints is repeatedly split in half and each new midpoint is pushed
+//! sideways by a random amount that shrinks with every subdivision. Interior
+//! points of a path can spawn branches, which are generated the same way with
+//! decayed length, jitter and branching probability.
+
+use bevy::prelude::*;
+use rand::Rng;
+use smart_default::SmartDefault;
+use std::f32::consts::TAU;
+
+#[derive(Debug, SmartDefault, Copy, Clone, PartialEq, Reflect)]
+pub struct BoltGeneratorConfig {
+    /// Number of midpoint displacement passes, the trunk ends up with `2^subdivisions` segments.
+    #[default(4)]
+    pub subdivisions: u32,
+    /// Max sideways displacement of the first midpoint relative to the bolt length.
+    #[default(0.15)]
+    pub jitter: f32,
+    /// Multiplier applied to the displacement after each subdivision pass.
+    #[default(0.55)]
+    pub jitter_decay: f32,
+    /// Chance for an interior point of the trunk to spawn a branch.
+    #[default(0.2)]
+    pub branch_probability: f32,
+    /// Multiplier applied to length and branch probability for each level of branching.
+    #[default(0.5)]
+    pub branch_decay: f32,
+    /// Max angle between a branch and the path it splits off from.
+    #[default(35.0)]
+    pub branch_angle_deg: f32,
+    /// How many levels of branches can be nested.
+    #[default(2)]
+    pub max_branch_depth: u32,
+}
+
+/// A single polyline of a [`BoltTree`].
+#[derive(Debug, Default, Clone, PartialEq, Reflect)]
+pub struct BoltPath {
+    pub points: Vec<Vec3>,
+    /// 0 for the trunk, incremented for each level of branching.
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoltTree {
+    /// The trunk is always first, branches follow their parents.
+    pub paths: Vec<BoltPath>,
+}
+
+impl BoltTree {
+    /// Applies `f` to every point of every path.
+    pub fn map_points(mut self, mut f: impl FnMut(Vec3) -> Vec3) -> Self {
+        for path in self.paths.iter_mut() {
+            for point in path.points.iter_mut() {
+                *point = f(*point);
+            }
+        }
+        self
+    }
+}
+
+/// Generates a bolt from `start` to `end`.
+pub fn generate_bolt<R: Rng>(
+    rng: &mut R,
+    start: Vec3,
+    end: Vec3,
+    config: &BoltGeneratorConfig,
+) -> BoltTree {
+    let mut tree = BoltTree { paths: Vec::new() };
+    generate_path(rng, start, end, config, 0, &mut tree);
+    tree
+}
+
+/// Midpoint displacement between `start` and `end` without any branching.
+pub fn displace_midpoints<R: Rng>(
+    rng: &mut R,
+    start: Vec3,
+    end: Vec3,
+    subdivisions: u32,
+    jitter: f32,
+    jitter_decay: f32,
+) -> Vec<Vec3> {
+    let axis = end - start;
+    let length = axis.length();
+    let mut points = vec![start, end];
+    if length < f32::EPSILON {
+        return points;
+    }
+    let (perp1, perp2) = (axis / length).any_orthonormal_pair();
+
+    let mut max_offset = length * jitter;
+    for _ in 0..subdivisions {
+        let mut next = Vec::with_capacity(points.len() * 2 - 1);
+        for (ix, &a) in points.iter().enumerate() {
+            next.push(a);
+            let Some(&b) = points.get(ix + 1) else {
+                continue;
+            };
+            let angle = rng.random_range(0.0..TAU);
+            let magnitude = if max_offset > 0.0 {
+                rng.random_range(-max_offset..=max_offset)
+            } else {
+                0.0
+            };
+            let offset = (perp1 * angle.cos() + perp2 * angle.sin()) * magnitude;
+            next.push(a.midpoint(b) + offset);
+        }
+        points = next;
+        max_offset *= jitter_decay;
+    }
+    points
+}
+
+fn generate_path<R: Rng>(
+    rng: &mut R,
+    start: Vec3,
+    end: Vec3,
+    config: &BoltGeneratorConfig,
+    depth: u32,
+    tree: &mut BoltTree,
+) {
+    // Branches are shorter, so they need fewer subdivisions for the same detail.
+    let subdivisions = config.subdivisions.saturating_sub(depth).max(1);
+    let points = displace_midpoints(
+        rng,
+        start,
+        end,
+        subdivisions,
+        config.jitter,
+        config.jitter_decay,
+    );
+    let path_ix = tree.paths.len();
+    tree.paths.push(BoltPath { points, depth });
+
+    if depth >= config.max_branch_depth {
+        return;
+    }
+    let branch_probability =
+        (config.branch_probability * config.branch_decay.powi(depth as i32)).clamp(0.0, 1.0);
+    if branch_probability <= 0.0 {
+        return;
+    }
+
+    let max_angle = config.branch_angle_deg.to_radians();
+    let path_len = tree.paths[path_ix].points.len();
+    for ix in 1..path_len - 1 {
+        if !rng.random_bool(branch_probability as f64) {
+            continue;
+        }
+        let points = &tree.paths[path_ix].points;
+        let origin = points[ix];
+        let direction = (points[ix + 1] - points[ix - 1]).normalize_or_zero();
+        if direction == Vec3::ZERO {
+            continue;
+        }
+        let remaining = points[ix..]
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum::<f32>();
+        let length = remaining * config.branch_decay;
+        if length < f32::EPSILON {
+            continue;
+        }
+
+        // Tilt the direction away from the parent path around a random perpendicular axis.
+        let (perp1, perp2) = direction.any_orthonormal_pair();
+        let around = rng.random_range(0.0..TAU);
+        let axis = perp1 * around.cos() + perp2 * around.sin();
+        let angle = rng.random_range(0.0..=max_angle);
+        let branch_direction = Quat::from_axis_angle(axis, angle) * direction;
+
+        generate_path(
+            rng,
+            origin,
+            origin + branch_direction * length,
+            config,
+            depth + 1,
+            tree,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::{Prng, ZERO_SEED};
+    use rand::SeedableRng;
+
+    fn rng() -> Prng {
+        Prng::from_seed(ZERO_SEED)
+    }
+
+    fn no_branches() -> BoltGeneratorConfig {
+        BoltGeneratorConfig {
+            branch_probability: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn keeps_endpoints() {
+        let start = Vec3::new(1.0, 2.0, 3.0);
+        let end = Vec3::new(-10.0, 5.0, 20.0);
+        let tree = generate_bolt(&mut rng(), start, end, &BoltGeneratorConfig::default());
+        let trunk = &tree.paths[0].points;
+        assert_eq!(trunk.first(), Some(&start));
+        assert_eq!(trunk.last(), Some(&end));
+    }
+
+    #[test]
+    fn subdivision_point_count() {
+        for subdivisions in 0..6 {
+            let points = displace_midpoints(
+                &mut rng(),
+                Vec3::ZERO,
+                Vec3::X * 10.0,
+                subdivisions,
+                0.2,
+                0.5,
+            );
+            assert_eq!(points.len(), 2usize.pow(subdivisions) + 1);
+        }
+    }
+
+    #[test]
+    fn no_jitter_is_straight() {
+        let end = Vec3::new(3.0, 4.0, 0.0);
+        let points = displace_midpoints(&mut rng(), Vec3::ZERO, end, 4, 0.0, 0.5);
+        for point in points {
+            assert!(point.cross(end).length() < 1e-4, "{point} not on line");
+        }
+    }
+
+    #[test]
+    fn jitter_is_bounded() {
+        let end = Vec3::Z * 100.0;
+        let jitter = 0.1;
+        let points = displace_midpoints(&mut rng(), Vec3::ZERO, end, 6, jitter, 0.5);
+        // Sum of the geometric series of max offsets.
+        let max_offset = 100.0 * jitter * 2.0;
+        for point in points {
+            assert!(point.xy().length() <= max_offset + 1e-3);
+        }
+    }
+
+    #[test]
+    fn deterministic_for_seed() {
+        let config = BoltGeneratorConfig {
+            branch_probability: 0.5,
+            ..Default::default()
+        };
+        let a = generate_bolt(&mut rng(), Vec3::ZERO, Vec3::Y * 50.0, &config);
+        let b = generate_bolt(&mut rng(), Vec3::ZERO, Vec3::Y * 50.0, &config);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn no_branches_without_probability() {
+        let tree = generate_bolt(&mut rng(), Vec3::ZERO, Vec3::Y * 50.0, &no_branches());
+        assert_eq!(tree.paths.len(), 1);
+        assert_eq!(tree.paths[0].depth, 0);
+    }
+
+    #[test]
+    fn branches_respect_depth_and_start_on_parent() {
+        let config = BoltGeneratorConfig {
+            branch_probability: 1.0,
+            branch_decay: 1.0,
+            max_branch_depth: 2,
+            ..Default::default()
+        };
+        let tree = generate_bolt(&mut rng(), Vec3::ZERO, Vec3::Y * 50.0, &config);
+        assert!(tree.paths.len() > 1);
+        for (ix, branch) in tree.paths.iter().enumerate().skip(1) {
+            assert!(branch.depth >= 1 && branch.depth <= config.max_branch_depth);
+            // Branches are pushed after their parent, so it has to be found before them.
+            let has_parent = tree.paths[..ix].iter().any(|parent| {
+                parent.depth + 1 == branch.depth && parent.points.contains(&branch.points[0])
+            });
+            assert!(has_parent, "branch {ix} doesn't start on a parent path");
+        }
+    }
+
+    #[test]
+    fn degenerate_bolt() {
+        let tree = generate_bolt(
+            &mut rng(),
+            Vec3::ONE,
+            Vec3::ONE,
+            &BoltGeneratorConfig::default(),
+        );
+        assert_eq!(tree.paths.len(), 1);
+        assert_eq!(tree.paths[0].points, vec![Vec3::ONE, Vec3::ONE]);
+    }
+}
@@ -0,0 +1,189 @@
+//! Damage numbers floating up from whatever got hurt.
+//!
+//! [`Damaged`] events are summed per target and damage type for a short interval before they
+//! are shown, so damage over time reads as one number instead of one per tick.
+
+use crate::game::effects::floating_text::floating_text;
+use crate::game::health::{DamageType, Damaged};
+use crate::game::pause_controller::CosmeticSystems;
+use bevy::color::palettes::css::{ORANGE_RED, SKY_BLUE};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct CombatTextConfig {
+    /// Toggled in the settings menu.
+    #[default(true)]
+    pub enabled: bool,
+    /// Damage to a target is summed up for this long before it is shown.
+    #[default(0.4)]
+    pub interval_secs: f32,
+    #[default(0.8)]
+    pub lifetime_secs: f32,
+    /// How far the numbers rise over their lifetime.
+    #[default(20.0)]
+    pub rise: f32,
+    /// How far above the target origin the numbers start.
+    #[default(30.0)]
+    pub height: f32,
+    #[default(18.0)]
+    pub font_size: f32,
+}
+
+/// Damage summed up since the first hit `age_secs` ago.
+#[derive(Debug, Default)]
+struct PendingDamage {
+    amount: f32,
+    age_secs: f32,
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(Update, show_damage.in_set(CosmeticSystems));
+}
+
+fn damage_color(damage_type: DamageType) -> Color {
+    match damage_type {
+        DamageType::Electric => SKY_BLUE.into(),
+        DamageType::Physical => Color::srgb(0.95, 0.95, 0.95),
+        DamageType::Fire => ORANGE_RED.into(),
+    }
+}
+
+fn format_amount(amount: f32) -> String {
+    if amount < 10.0 {
+        format!("{amount:.1}")
+    } else {
+        format!("{amount:.0}")
+    }
+}
+
+fn show_damage(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<CombatTextConfig>,
+    mut damaged: EventReader<Damaged>,
+    mut pending: Local<HashMap<(Entity, DamageType), PendingDamage>>,
+    targets: Query<&GlobalTransform>,
+) {
+    if !config.enabled {
+        damaged.clear();
+        pending.clear();
+        return;
+    }
+    for event in damaged.read() {
+        pending
+            .entry((event.target, event.damage_type))
+            .or_default()
+            .amount += event.amount;
+    }
+    let dt = time.delta_secs();
+    pending.retain(|&(target, damage_type), damage| {
+        damage.age_secs += dt;
+        if damage.age_secs < config.interval_secs {
+            return true;
+        }
+        // Targets that are gone by now don't get a number.
+        if let Ok(transform) = targets.get(target) {
+            commands.spawn(floating_text(
+                format_amount(damage.amount),
+                damage_color(damage_type),
+                config.font_size,
+                transform.translation() + Vec3::Y * config.height,
+                config.rise,
+                config.lifetime_secs,
+            ));
+        }
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::effects::floating_text::FloatingText;
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+        app.add_event::<Damaged>();
+        app.init_resource::<CombatTextConfig>();
+        app.add_systems(Update, show_damage);
+        app
+    }
+
+    fn damaged(target: Entity, amount: f32, damage_type: DamageType) -> Damaged {
+        Damaged {
+            target,
+            source: None,
+            amount,
+            damage_type,
+            hops: 0,
+        }
+    }
+
+    fn shown_texts(app: &mut App) -> Vec<String> {
+        let mut texts = app
+            .world_mut()
+            .query_filtered::<&Text, With<FloatingText>>()
+            .iter(app.world())
+            .map(|text| text.0.clone())
+            .collect::<Vec<_>>();
+        texts.sort();
+        texts
+    }
+
+    #[test]
+    fn hits_within_an_interval_show_one_sum() {
+        let mut app = test_app();
+        let target = app.world_mut().spawn(GlobalTransform::default()).id();
+        let gone = app.world_mut().spawn(GlobalTransform::default()).id();
+        app.world_mut().send_event_batch([
+            damaged(target, 4.0, DamageType::Electric),
+            damaged(target, 5.5, DamageType::Electric),
+            damaged(gone, 7.0, DamageType::Electric),
+        ]);
+        app.world_mut().despawn(gone);
+
+        app.update();
+        app.world_mut()
+            .send_event(damaged(target, 2.5, DamageType::Electric));
+        app.update();
+        assert!(
+            shown_texts(&mut app).is_empty(),
+            "shown before the interval"
+        );
+
+        for _ in 0..5 {
+            app.update();
+        }
+        assert_eq!(shown_texts(&mut app), vec!["12".to_string()]);
+    }
+
+    #[test]
+    fn damage_types_are_shown_apart() {
+        let mut app = test_app();
+        let target = app.world_mut().spawn(GlobalTransform::default()).id();
+        app.world_mut().send_event_batch([
+            damaged(target, 20.0, DamageType::Electric),
+            damaged(target, 3.0, DamageType::Physical),
+        ]);
+        for _ in 0..6 {
+            app.update();
+        }
+        assert_eq!(
+            shown_texts(&mut app),
+            vec!["20".to_string(), "3.0".to_string()]
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! UI text anchored to a point in the world that rises and fades out, for score popups and
+//! damage numbers.
+
+use crate::game::camera::MainCamera;
+use crate::game::pause_controller::CosmeticSystems;
+use crate::game::screens::Screen;
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct FloatingText {
+    pub anchor: Vec3,
+    /// How far the text rises over its lifetime.
+    pub rise: f32,
+    timer: Timer,
+}
+
+/// `text` floating up from `anchor` for `lifetime_secs`.
+pub fn floating_text(
+    text: impl Into<String>,
+    color: Color,
+    font_size: f32,
+    anchor: Vec3,
+    rise: f32,
+    lifetime_secs: f32,
+) -> impl Bundle {
+    (
+        Name::new("Floating Text"),
+        FloatingText {
+            anchor,
+            rise,
+            timer: Timer::from_seconds(lifetime_secs, TimerMode::Once),
+        },
+        StateScoped(Screen::Gameplay),
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        Text(text.into()),
+        TextFont::from_font_size(font_size),
+        TextColor(color),
+        // Hidden until placed on screen.
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    )
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(Update, animate.in_set(CosmeticSystems));
+}
+
+fn animate(
+    mut commands: Commands,
+    time: Res<Time>,
+    camera: Option<Single<(&Camera, &GlobalTransform), With<MainCamera>>>,
+    mut texts: Query<(
+        Entity,
+        &mut FloatingText,
+        &mut Node,
+        &mut TextColor,
+        &mut Visibility,
+    )>,
+) {
+    let Some(camera) = camera else {
+        return;
+    };
+    let (camera, camera_transform) = *camera;
+    for (entity, mut text, mut node, mut color, mut visibility) in texts.iter_mut() {
+        if text.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = text.timer.fraction();
+        let position = text.anchor + Vec3::Y * text.rise * t;
+        let Ok(viewport) = camera.world_to_viewport(camera_transform, position) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+        node.left = Px(viewport.x);
+        node.top = Px(viewport.y);
+        color.0.set_alpha(1.0 - t * t);
+    }
+}
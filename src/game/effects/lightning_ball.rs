@@ -1,7 +1,10 @@
 // TODO: split bolt into component marker with behavior and effects
 
-use crate::game::rng::global::GlobalRng;
-use crate::game::rng::sphere::RandomSpherePoint;
+use crate::game::effects::bolt::{Bolt, BoltStyle, bolt};
+use crate::game::effects::bolt_generator::{BoltGeneratorConfig, generate_bolt};
+use crate::game::pause_controller::CosmeticSystems;
+use crate::game::rng::sphere::{RandomSpherePoint, sample_direction_in_hemisphere};
+use crate::game::rng::stream::CosmeticRng;
 use avian3d::prelude::{
     Collider, CollidingEntities, Collisions, Position, RigidBody, Rotation, Sensor,
 };
@@ -9,11 +12,11 @@ use bevy::color::palettes::css::SKY_BLUE;
 use bevy::ecs::query::QueryData;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
-use itertools::Itertools;
 use rand::Rng;
 use smart_default::SmartDefault;
 use std::f32::consts::TAU;
 use std::ops::RangeInclusive;
+use std::time::Duration;
 
 #[auto_register_type]
 #[auto_name]
@@ -23,6 +26,8 @@ use std::ops::RangeInclusive;
 #[require(Transform)]
 #[require(LightningBallConfig)]
 #[require(LightningBallSources)]
+#[require(LightningBallBoltTimer)]
+#[require(CosmeticRng)]
 pub struct LightningBall;
 
 #[auto_register_type]
@@ -33,21 +38,50 @@ pub struct LightningBallConfig {
     pub spark_radius_range: RangeInclusive<f32>,
     #[default(DEFAULT_LIGHTNING_BALL_SPARK_COUNT)]
     pub spark_count: usize,
-    #[default(DEFAULT_LIGHTNING_BALL_SPARK_SEGMENT_COUNT)]
-    pub spark_segment_count: usize,
+    /// Length of the sparks crawling over the surface, relative to the circumference.
     #[default(DEFAULT_LIGHTNING_BALL_SPARK_SEGMENT_LEN_PERC)]
     pub spark_segment_len_perc: f32,
-    #[default(DEFAULT_LIGHTNING_BALL_SPARK_SEGMENT_MAX_ANGLE_DEG)]
-    pub spark_segment_max_angle_deg: f32,
+    /// Shape of the sparks crawling over the surface.
+    #[default(BoltGeneratorConfig {
+        subdivisions: 3,
+        jitter: 0.3,
+        branch_probability: 0.1,
+        max_branch_depth: 1,
+        ..Default::default()
+    })]
+    pub spark_bolt: BoltGeneratorConfig,
+    /// Shape of the bolts reaching out to conduits in range.
+    #[default(BoltGeneratorConfig::default())]
+    pub conduit_bolt: BoltGeneratorConfig,
+    /// How long each generated bolt stays alive before new ones are generated.
+    #[default(DEFAULT_LIGHTNING_BALL_BOLT_LIFETIME_SECS)]
+    pub bolt_lifetime_secs: f32,
+    #[default(DEFAULT_LIGHTNING_BALL_BOLT_WIDTH)]
+    pub bolt_width: f32,
 }
 
 pub const DEFAULT_LIGHTNING_BALL_RADIUS: f32 = 0.5;
 pub const DEFAULT_LIGHTNING_BALL_SPARK_RADIUS_MIN: f32 = 1.0;
 pub const DEFAULT_LIGHTNING_BALL_SPARK_RADIUS_MAX: f32 = 1.1;
 pub const DEFAULT_LIGHTNING_BALL_SPARK_COUNT: usize = 10;
-pub const DEFAULT_LIGHTNING_BALL_SPARK_SEGMENT_COUNT: usize = 3;
 pub const DEFAULT_LIGHTNING_BALL_SPARK_SEGMENT_LEN_PERC: f32 = 0.25;
-pub const DEFAULT_LIGHTNING_BALL_SPARK_SEGMENT_MAX_ANGLE_DEG: f32 = 45.0;
+pub const DEFAULT_LIGHTNING_BALL_BOLT_LIFETIME_SECS: f32 = 0.08;
+pub const DEFAULT_LIGHTNING_BALL_BOLT_WIDTH: f32 = 0.04;
+
+/// Controls how often a [`LightningBall`] regenerates its bolts.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct LightningBallBoltTimer(Timer);
+
+impl Default for LightningBallBoltTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(
+            DEFAULT_LIGHTNING_BALL_BOLT_LIFETIME_SECS,
+            TimerMode::Repeating,
+        ))
+    }
+}
 
 #[auto_register_type]
 #[derive(Component, Debug, Default, Clone, Reflect)]
@@ -100,7 +134,7 @@ impl FromWorld for LightningBallMeshMaterialCache {
     }
 }
 
-fn on_lightning_ball_added(
+pub(crate) fn on_lightning_ball_added(
     trigger: Trigger<OnAdd, LightningBall>,
     mut commands: Commands,
     material_cache: Res<LightningBallMeshMaterialCache>,
@@ -138,6 +172,8 @@ pub struct LightningBallQueryData {
     pub global_transform: Ref<'static, GlobalTransform>,
     pub point_light: Mut<'static, PointLight>,
     pub lightning_ball_config: Mut<'static, LightningBallConfig>,
+    pub bolt_timer: Ref<'static, LightningBallBoltTimer>,
+    pub cosmetic_rng: Mut<'static, CosmeticRng>,
     lighting_ball_sources: Ref<'static, LightningBallSources>,
 }
 
@@ -151,12 +187,34 @@ pub struct LightningBallSourceQueryData {
     pub colliding_entities: Ref<'static, CollidingEntities>,
 }
 
+pub(crate) fn tick_bolt_timers(
+    time: Res<Time>,
+    mut lightning_balls_q: Query<(&mut LightningBallBoltTimer, &LightningBallConfig)>,
+) {
+    for (mut timer, config) in lightning_balls_q.iter_mut() {
+        let duration = Duration::from_secs_f32(config.bolt_lifetime_secs.max(0.0));
+        if timer.0.duration() != duration {
+            timer.0.set_duration(duration);
+        }
+        timer.0.tick(time.delta());
+    }
+}
+
+fn bolt_style(config: &LightningBallConfig, scale: f32) -> BoltStyle {
+    BoltStyle {
+        width: config.bolt_width * scale,
+        ..Default::default()
+    }
+}
+
 fn animate(
-    mut gizmos: Gizmos,
-    mut rng: GlobalRng,
-    lightning_balls_q: Query<LightningBallQueryData, With<LightningBall>>,
+    mut commands: Commands,
+    mut lightning_balls_q: Query<LightningBallQueryData, With<LightningBall>>,
 ) {
-    for lb in lightning_balls_q.iter() {
+    for mut lb in lightning_balls_q.iter_mut() {
+        if !lb.bolt_timer.0.just_finished() {
+            continue;
+        }
         // Prevent crash during inspector editing and resulting in empty range
         if lb.lightning_ball_config.spark_radius_range.is_empty() {
             continue;
@@ -165,83 +223,51 @@ fn animate(
         let scaled_radius_min = lb.lightning_ball_config.spark_radius_range.start() * scale;
         let scaled_radius_max = lb.lightning_ball_config.spark_radius_range.end() * scale;
         let scaled_target_radius = (scaled_radius_min + scaled_radius_max) / 2.0;
-        let total_spark_segment_length =
-            TAU * lb.lightning_ball_config.spark_segment_len_perc * scale;
-        let spark_segment_length =
-            total_spark_segment_length / lb.lightning_ball_config.spark_segment_count as f32;
+        let spark_length = TAU * lb.lightning_ball_config.spark_segment_len_perc * scale;
+        // Angle the spark covers when following the sphere's surface.
+        let spark_arc = spark_length / scaled_target_radius;
         let center = lb.global_transform.translation();
 
         for _ in 0..lb.lightning_ball_config.spark_count {
             // Pick a random starting point on the sphere:
-            let transform_point = (*rng.rng()).random_sphere_point(scaled_target_radius);
-
-            // Build a tangent‐space basis at transform_point:
-            let normal = transform_point.normalize();
-            // Pick any vector not parallel to `normal`:
-            let helper = if normal.x.abs() < 0.9 {
-                Vec3::X
-            } else {
-                Vec3::Y
-            };
-            // Gram–Schmidt to get a unit tangent:
-            let tangent = (helper - normal * normal.dot(helper)).normalize();
-            let bitangent = normal.cross(tangent);
-
-            // Choose a truly random initial direction in that tangent plane:
-            let phi = rng.rng().random_range(0.0f32..TAU);
-            let mut direction = tangent * phi.cos() + bitangent * phi.sin();
-            // Note: `direction` is a unit‐length vector, tangent to the sphere at transform_point.
-
-            // Build the list of points (in *local* sphere coordinates):
-            let mut points: Vec<Vec3> =
-                Vec::with_capacity(lb.lightning_ball_config.spark_segment_count + 1);
-            points.push(transform_point);
-            let mut last = transform_point;
-
-            let max_angle = lb
-                .lightning_ball_config
-                .spark_segment_max_angle_deg
-                .to_radians();
-
-            for _ in 0..lb.lightning_ball_config.spark_segment_count {
-                // Rotate `direction` by a small random angle *around* the local normal:
-                // (This “wiggles” the direction within the tangent plane.)
-                let theta = rng.rng().random_range(-max_angle..=max_angle);
-                let rot = Quat::from_axis_angle(normal, theta);
-                direction = (rot * direction).normalize();
-
-                // Take a small step “forward” along that tangent:
-                let raw_offset = direction * spark_segment_length;
-                let raw_next = last + raw_offset;
-
-                // Pick a random radius in [min..max] for this new point:
-                let rand_radius = rng
-                    .rng()
-                    .random_range(scaled_radius_min..=scaled_radius_max);
-
-                // Project raw_next onto the sphere of that random radius:
-                let next_on_sphere = raw_next.normalize() * rand_radius;
-                points.push(next_on_sphere);
-                last = next_on_sphere;
-            }
-
-            // Translate each point into world space and draw the polyline:
-            let world_pts: Vec<Vec3> = points
-                .into_iter()
-                .map(|p| Transform::from_translation(center).transform_point(p))
-                .collect();
-
-            for (&a, &b) in world_pts.iter().tuple_windows() {
-                gizmos.line_gradient(a, b, Color::WHITE, Color::from(SKY_BLUE));
-            }
+            let start = lb.cosmetic_rng.random_sphere_point(scaled_target_radius);
+
+            // Pick a random direction tangent to the sphere at `start`:
+            let normal = start.normalize();
+            let (tangent, bitangent) = normal.any_orthonormal_pair();
+            let phi = lb.cosmetic_rng.random_range(0.0f32..TAU);
+            let direction = tangent * phi.cos() + bitangent * phi.sin();
+
+            // Travel along the surface in that direction to find the end point:
+            let end = Quat::from_axis_angle(normal.cross(direction), spark_arc) * start;
+
+            // The generator works on the straight chord, push every point back
+            // onto the shell so the spark hugs the surface:
+            let tree = generate_bolt(
+                &mut **lb.cosmetic_rng,
+                start,
+                end,
+                &lb.lightning_ball_config.spark_bolt,
+            )
+            .map_points(|p| {
+                let radius = p.length().clamp(scaled_radius_min, scaled_radius_max);
+                center + p.normalize_or(normal) * radius
+            });
+
+            let phase = lb.cosmetic_rng.random_range(0.0..1.0);
+            commands.spawn(bolt(
+                Bolt::from(tree),
+                bolt_style(&lb.lightning_ball_config, scale),
+                lb.lightning_ball_config.bolt_lifetime_secs,
+                phase,
+            ));
         }
     }
 }
 
-fn animate_in_range(
-    mut gizmos: Gizmos,
-    mut rng: GlobalRng,
-    lightning_balls_q: Query<
+pub(crate) fn animate_in_range(
+    mut commands: Commands,
+    mut lightning_balls_q: Query<
         LightningBallQueryData,
         (With<LightningBall>, Without<LightningBallSource>),
     >,
@@ -252,7 +278,10 @@ fn animate_in_range(
     colliding_q: Query<(&Position, &Rotation), With<LightningBallConduit>>,
     collisions: Collisions,
 ) {
-    for lb in lightning_balls_q.iter() {
+    for mut lb in lightning_balls_q.iter_mut() {
+        if !lb.bolt_timer.0.just_finished() {
+            continue;
+        }
         // Prevent crash during inspector editing and resulting in empty range
         if lb.lightning_ball_config.spark_radius_range.is_empty() {
             continue;
@@ -265,20 +294,14 @@ fn animate_in_range(
             let Ok(lb_source) = lightning_balls_source_q.get(lightning_ball_source_entity) else {
                 continue;
             };
-            for _ in 0..lb.lightning_ball_config.spark_count {
-                for &colliding_entity in lb_source.colliding_entities.iter() {
-                    let Ok((position, rotation)) = colliding_q.get(colliding_entity) else {
-                        continue;
-                    };
-
-                    let Some(cp) = collisions.get(lb_source.entity, colliding_entity) else {
-                        continue;
-                    };
-
-                    let Some(contact) = cp.find_deepest_contact() else {
-                        continue;
-                    };
-
+            // Contact points only depend on the conduit, look them up once per source.
+            let target_points = lb_source
+                .colliding_entities
+                .iter()
+                .filter_map(|&colliding_entity| {
+                    let (position, rotation) = colliding_q.get(colliding_entity).ok()?;
+                    let cp = collisions.get(lb_source.entity, colliding_entity)?;
+                    let contact = cp.find_deepest_contact()?;
                     let target_world_pos = if cp.collider1 == colliding_entity {
                         contact.global_point1(position, rotation)
                     } else if cp.collider2 == colliding_entity {
@@ -286,69 +309,39 @@ fn animate_in_range(
                     } else {
                         unreachable!("bad collision");
                     };
-
                     // Direction + distance from ball center → target
                     let to_target = target_world_pos - center;
-                    if to_target.length_squared() < f32::EPSILON {
-                        continue;
-                    }
-                    let n = to_target.normalize(); // “pole” for our hemisphere
-                    let full_distance = to_target.length();
+                    (to_target.length_squared() >= f32::EPSILON)
+                        .then(|| (target_world_pos, to_target.normalize()))
+                })
+                .collect::<Vec<_>>();
 
+            for _ in 0..lb.lightning_ball_config.spark_count {
+                // n is the “pole” for our hemisphere
+                for &(target_world_pos, n) in &target_points {
                     // Sphere’s outer radius (so we launch exactly from the curved face):
                     let sphere_radius: f32 = scaled_radius_max;
 
-                    // Build an orthonormal basis {perp1, perp2} ⟂ n:
-                    let helper = if n.x.abs() < 0.9 { Vec3::X } else { Vec3::Y };
-                    let perp1 = (helper - n * n.dot(helper)).normalize();
-                    let perp2 = n.cross(perp1);
-
                     // Sample a random direction on the hemisphere whose “pole” is n:
-                    let phi = rng.rng().random_range(0.0f32..TAU);
-                    let cos_theta = rng.rng().random_range(0.0f32..=1.0f32);
-                    let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
-                    let hemisphere_dir = perp1 * (sin_theta * phi.cos())
-                        + perp2 * (sin_theta * phi.sin())
-                        + n * (cos_theta);
+                    let hemisphere_dir = sample_direction_in_hemisphere(&mut **lb.cosmetic_rng, n);
 
                     // Compute the random start point on that hemisphere of the sphere:
                     let start_point: Vec3 = center + hemisphere_dir * sphere_radius;
 
-                    // TODO: space out bends to make it more organic
-                    // Build a jagged bolt from start_point → target_world_pos:
-                    //    interpolate from “start_point on the sphere surface” to the exact target,
-                    //    adding a small sideways jitter at each interior segment.
-                    let mut bolt_points: Vec<Vec3> =
-                        Vec::with_capacity(lb.lightning_ball_config.spark_segment_count + 2);
-                    bolt_points.push(start_point);
-
-                    // Precompute straight vs lateral:
-                    let segments = lb.lightning_ball_config.spark_segment_count as f32;
-                    for i in 1..=lb.lightning_ball_config.spark_segment_count {
-                        let t = (i as f32) / (segments + 1.0);
-                        // Interpolate the distance from start_radius → full_distance:
-                        let distance_along_line =
-                            sphere_radius + (full_distance - sphere_radius) * t;
-                        let ideal_point = center + n * distance_along_line;
-
-                        // Sideways jitter: zero at t=0, zero at t=1, peaks at t=0.5
-                        let max_offset = full_distance * 0.05 * (1.0 - (2.0 * (t - 0.5)).abs());
-                        let mag = rng.rng().random_range(-max_offset..=max_offset);
-                        let angle = rng.rng().random_range(0.0f32..TAU);
-                        let sideways_offset =
-                            perp1 * (angle.cos() * mag) + perp2 * (angle.sin() * mag);
-
-                        let next_pt = ideal_point + sideways_offset;
-                        bolt_points.push(next_pt);
-                    }
-
-                    // End at the collider’s transform:
-                    bolt_points.push(target_world_pos);
-
-                    // Draw
-                    for (&a, &b) in bolt_points.iter().tuple_windows() {
-                        gizmos.line_gradient(a, b, Color::WHITE, Color::from(SKY_BLUE));
-                    }
+                    let tree = generate_bolt(
+                        &mut **lb.cosmetic_rng,
+                        start_point,
+                        target_world_pos,
+                        &lb.lightning_ball_config.conduit_bolt,
+                    );
+
+                    let phase = lb.cosmetic_rng.random_range(0.0..1.0);
+                    commands.spawn(bolt(
+                        Bolt::from(tree),
+                        bolt_style(&lb.lightning_ball_config, scale),
+                        lb.lightning_ball_config.bolt_lifetime_secs,
+                        phase,
+                    ));
                 }
             }
         }
@@ -358,5 +351,10 @@ fn animate_in_range(
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
     app.add_observer(on_lightning_ball_added);
-    app.add_systems(Update, (animate, animate_in_range));
+    app.add_systems(
+        Update,
+        (tick_bolt_timers, (animate, animate_in_range))
+            .chain()
+            .in_set(CosmeticSystems),
+    );
 }
@@ -1,9 +1,18 @@
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
+pub mod bolt;
+pub mod bolt_generator;
+pub mod combat_text;
+pub mod floating_text;
 pub mod lightning_ball;
+pub mod particles;
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
+    app.add_plugins(bolt::plugin);
+    app.add_plugins(combat_text::plugin);
+    app.add_plugins(floating_text::plugin);
     app.add_plugins(lightning_ball::plugin);
+    app.add_plugins(particles::plugin);
 }
@@ -0,0 +1,382 @@
+//! GPU particle effects via [`bevy_hanabi`].
+//!
+//! Gameplay code requests a one-shot effect by writing a [`SpawnParticleEffect`]
+//! event, attached effects (trails, ambient crackle) are added by observers.
+//!
+//! Hanabi simulates on `Time<EffectSimulation>`, which follows virtual time, so particles in
+//! flight freeze with the game while it is paused.
+
+use crate::game::despawn::DespawnDelayed;
+use crate::game::effects::lightning_ball::LightningBall;
+use crate::game::health::{Damaged, Dead};
+use crate::game::pause_controller::CosmeticSystems;
+use crate::game::spark::Spark;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use bevy_hanabi::prelude::*;
+use smart_default::SmartDefault;
+
+/// Every effect in the library.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+pub enum ParticleEffectKind {
+    /// Continuous trail left behind a moving [`Spark`].
+    SparkTrail,
+    /// Short burst where damage lands.
+    Impact,
+    /// Puff of smoke when something dies.
+    DeathPuff,
+    /// Continuous crackle around a [`LightningBall`].
+    Crackle,
+}
+
+impl ParticleEffectKind {
+    /// How long a spawned effect entity lives before getting despawned,
+    /// continuous effects live until their parent is despawned.
+    fn lifetime_secs(&self) -> Option<f32> {
+        match self {
+            Self::SparkTrail | Self::Crackle => None,
+            Self::Impact => Some(0.5),
+            Self::DeathPuff => Some(1.5),
+        }
+    }
+}
+
+/// Request to spawn a one-shot effect at a world position.
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone)]
+pub struct SpawnParticleEffect {
+    pub kind: ParticleEffectKind,
+    pub position: Vec3,
+}
+
+impl SpawnParticleEffect {
+    pub fn at(kind: ParticleEffectKind, position: Vec3) -> Self {
+        Self { kind, position }
+    }
+}
+
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+struct ParticleEffectLifetime(Timer);
+
+/// Minimum time between two impact effects on the same target,
+/// damage over time would otherwise spawn one every frame.
+const IMPACT_COOLDOWN_SECS: f32 = 0.25;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ParticleConfig {
+    /// Height above the dead entity's origin the death puff is centered on.
+    #[default(10.0)]
+    pub death_puff_height: f32,
+    /// Radius of the death puff. Baked into the effect when the library is built.
+    #[default(5.0)]
+    pub death_puff_radius: f32,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct ParticleEffectLibrary {
+    spark_trail: Handle<EffectAsset>,
+    impact: Handle<EffectAsset>,
+    death_puff: Handle<EffectAsset>,
+    crackle: Handle<EffectAsset>,
+}
+
+impl ParticleEffectLibrary {
+    pub fn get(&self, kind: ParticleEffectKind) -> Handle<EffectAsset> {
+        match kind {
+            ParticleEffectKind::SparkTrail => &self.spark_trail,
+            ParticleEffectKind::Impact => &self.impact,
+            ParticleEffectKind::DeathPuff => &self.death_puff,
+            ParticleEffectKind::Crackle => &self.crackle,
+        }
+        .clone()
+    }
+
+    /// A bundle playing the effect, to be spawned or added as a child.
+    pub fn effect(&self, kind: ParticleEffectKind) -> impl Bundle {
+        (
+            Name::new(format!("{kind:?} Effect")),
+            ParticleEffect::new(self.get(kind)),
+        )
+    }
+}
+
+impl FromWorld for ParticleEffectLibrary {
+    fn from_world(world: &mut World) -> Self {
+        let config = *world.get_resource_or_init::<ParticleConfig>();
+        let mut effects = world.resource_mut::<Assets<EffectAsset>>();
+        Self {
+            spark_trail: effects.add(spark_trail_effect()),
+            impact: effects.add(impact_effect()),
+            death_puff: effects.add(death_puff_effect(&config)),
+            crackle: effects.add(crackle_effect()),
+        }
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<ParticleEffectLibrary>();
+    app.add_observer(on_spark_added);
+    app.add_observer(on_lightning_ball_added);
+    app.add_observer(on_dead_added);
+    app.add_systems(
+        Update,
+        (impact_on_damage, spawn_effects, tick_lifetime)
+            .chain()
+            .in_set(CosmeticSystems),
+    );
+}
+
+fn spawn_effects(
+    mut commands: Commands,
+    mut spawn_effect_events: EventReader<SpawnParticleEffect>,
+    library: Res<ParticleEffectLibrary>,
+) {
+    for &SpawnParticleEffect { kind, position } in spawn_effect_events.read() {
+        let mut effect =
+            commands.spawn((library.effect(kind), Transform::from_translation(position)));
+        if let Some(lifetime_secs) = kind.lifetime_secs() {
+            effect.insert(ParticleEffectLifetime(Timer::from_seconds(
+                lifetime_secs,
+                TimerMode::Once,
+            )));
+        }
+    }
+}
+
+fn tick_lifetime(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut effects: Query<(Entity, &mut ParticleEffectLifetime)>,
+) {
+    for (entity, mut lifetime) in effects.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(entity).trigger(DespawnDelayed);
+        }
+    }
+}
+
+fn impact_on_damage(
+    time: Res<Time>,
+    mut last_impact: Local<HashMap<Entity, f32>>,
+    mut damaged_events: EventReader<Damaged>,
+    mut spawn_effect_events: EventWriter<SpawnParticleEffect>,
+    targets: Query<&GlobalTransform>,
+) {
+    let now = time.elapsed_secs();
+    last_impact.retain(|_, at| now - *at < IMPACT_COOLDOWN_SECS);
+    for event in damaged_events.read() {
+        if last_impact.contains_key(&event.target) {
+            continue;
+        }
+        let Ok(transform) = targets.get(event.target) else {
+            continue;
+        };
+        last_impact.insert(event.target, now);
+        spawn_effect_events.write(SpawnParticleEffect::at(
+            ParticleEffectKind::Impact,
+            transform.translation(),
+        ));
+    }
+}
+
+fn on_dead_added(
+    trigger: Trigger<OnAdd, Dead>,
+    transforms: Query<&GlobalTransform, Without<Spark>>,
+    config: Res<ParticleConfig>,
+    mut spawn_effect_events: EventWriter<SpawnParticleEffect>,
+) {
+    let Ok(transform) = transforms.get(trigger.target()) else {
+        return;
+    };
+    spawn_effect_events.write(SpawnParticleEffect::at(
+        ParticleEffectKind::DeathPuff,
+        transform.translation() + Vec3::Y * config.death_puff_height,
+    ));
+}
+
+fn on_spark_added(
+    trigger: Trigger<OnAdd, Spark>,
+    mut commands: Commands,
+    library: Res<ParticleEffectLibrary>,
+) {
+    commands
+        .entity(trigger.target())
+        .with_child(library.effect(ParticleEffectKind::SparkTrail));
+}
+
+fn on_lightning_ball_added(
+    trigger: Trigger<OnAdd, LightningBall>,
+    mut commands: Commands,
+    library: Res<ParticleEffectLibrary>,
+) {
+    commands
+        .entity(trigger.target())
+        .with_child(library.effect(ParticleEffectKind::Crackle));
+}
+
+// Effect assets
+
+fn color_gradient(keys: &[(f32, Vec4)]) -> Gradient<Vec4> {
+    let mut gradient = Gradient::new();
+    for &(ratio, color) in keys {
+        gradient.add_key(ratio, color);
+    }
+    gradient
+}
+
+fn size_gradient(keys: &[(f32, f32)]) -> Gradient<Vec3> {
+    let mut gradient = Gradient::new();
+    for &(ratio, size) in keys {
+        gradient.add_key(ratio, Vec3::splat(size));
+    }
+    gradient
+}
+
+fn spark_trail_effect() -> EffectAsset {
+    let writer = ExprWriter::new();
+    let init_pos = SetPositionSphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        radius: writer.lit(0.2).expr(),
+        dimension: ShapeDimension::Volume,
+    };
+    let init_vel = SetVelocitySphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        speed: writer.lit(0.5).uniform(writer.lit(2.0)).expr(),
+    };
+    let init_age = SetAttributeModifier::new(Attribute::AGE, writer.lit(0.0).expr());
+    let init_lifetime = SetAttributeModifier::new(
+        Attribute::LIFETIME,
+        writer.lit(0.2).uniform(writer.lit(0.5)).expr(),
+    );
+    let drag = LinearDragModifier::new(writer.lit(4.0).expr());
+
+    EffectAsset::new(1024, SpawnerSettings::rate(60.0.into()), writer.finish())
+        .with_name("spark_trail")
+        .init(init_pos)
+        .init(init_vel)
+        .init(init_age)
+        .init(init_lifetime)
+        .update(drag)
+        .render(ColorOverLifetimeModifier::new(color_gradient(&[
+            (0.0, Vec4::new(8.0, 8.0, 10.0, 1.0)),
+            (0.3, Vec4::new(1.0, 3.0, 8.0, 1.0)),
+            (1.0, Vec4::new(0.2, 0.5, 2.0, 0.0)),
+        ])))
+        .render(SizeOverLifetimeModifier {
+            gradient: size_gradient(&[(0.0, 0.3), (1.0, 0.0)]),
+            screen_space_size: false,
+        })
+}
+
+fn impact_effect() -> EffectAsset {
+    let writer = ExprWriter::new();
+    let init_pos = SetPositionSphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        radius: writer.lit(0.5).expr(),
+        dimension: ShapeDimension::Surface,
+    };
+    let init_vel = SetVelocitySphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        speed: writer.lit(10.0).uniform(writer.lit(25.0)).expr(),
+    };
+    let init_age = SetAttributeModifier::new(Attribute::AGE, writer.lit(0.0).expr());
+    let init_lifetime = SetAttributeModifier::new(
+        Attribute::LIFETIME,
+        writer.lit(0.15).uniform(writer.lit(0.35)).expr(),
+    );
+    let gravity = AccelModifier::new(writer.lit(Vec3::Y * -30.0).expr());
+
+    EffectAsset::new(512, SpawnerSettings::once(32.0.into()), writer.finish())
+        .with_name("impact")
+        .init(init_pos)
+        .init(init_vel)
+        .init(init_age)
+        .init(init_lifetime)
+        .update(gravity)
+        .render(ColorOverLifetimeModifier::new(color_gradient(&[
+            (0.0, Vec4::new(10.0, 10.0, 10.0, 1.0)),
+            (0.5, Vec4::new(2.0, 4.0, 10.0, 1.0)),
+            (1.0, Vec4::new(0.5, 1.0, 4.0, 0.0)),
+        ])))
+        .render(SizeOverLifetimeModifier {
+            gradient: size_gradient(&[(0.0, 0.4), (1.0, 0.05)]),
+            screen_space_size: false,
+        })
+}
+
+fn death_puff_effect(config: &ParticleConfig) -> EffectAsset {
+    let writer = ExprWriter::new();
+    let init_pos = SetPositionSphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        radius: writer.lit(config.death_puff_radius).expr(),
+        dimension: ShapeDimension::Volume,
+    };
+    let init_vel = SetVelocitySphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        speed: writer.lit(2.0).uniform(writer.lit(6.0)).expr(),
+    };
+    let init_age = SetAttributeModifier::new(Attribute::AGE, writer.lit(0.0).expr());
+    let init_lifetime = SetAttributeModifier::new(
+        Attribute::LIFETIME,
+        writer.lit(0.8).uniform(writer.lit(1.4)).expr(),
+    );
+    let rise = AccelModifier::new(writer.lit(Vec3::Y * 4.0).expr());
+    let drag = LinearDragModifier::new(writer.lit(2.0).expr());
+
+    EffectAsset::new(512, SpawnerSettings::once(64.0.into()), writer.finish())
+        .with_name("death_puff")
+        .init(init_pos)
+        .init(init_vel)
+        .init(init_age)
+        .init(init_lifetime)
+        .update(rise)
+        .update(drag)
+        .render(ColorOverLifetimeModifier::new(color_gradient(&[
+            (0.0, Vec4::new(0.6, 0.6, 0.6, 0.8)),
+            (1.0, Vec4::new(0.2, 0.2, 0.2, 0.0)),
+        ])))
+        .render(SizeOverLifetimeModifier {
+            gradient: size_gradient(&[(0.0, 1.0), (1.0, 4.0)]),
+            screen_space_size: false,
+        })
+}
+
+fn crackle_effect() -> EffectAsset {
+    let writer = ExprWriter::new();
+    let init_pos = SetPositionSphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        radius: writer.lit(1.2).expr(),
+        dimension: ShapeDimension::Surface,
+    };
+    let init_vel = SetVelocitySphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        speed: writer.lit(0.5).uniform(writer.lit(1.5)).expr(),
+    };
+    let init_age = SetAttributeModifier::new(Attribute::AGE, writer.lit(0.0).expr());
+    let init_lifetime = SetAttributeModifier::new(
+        Attribute::LIFETIME,
+        writer.lit(0.05).uniform(writer.lit(0.2)).expr(),
+    );
+
+    EffectAsset::new(256, SpawnerSettings::rate(40.0.into()), writer.finish())
+        .with_name("crackle")
+        .init(init_pos)
+        .init(init_vel)
+        .init(init_age)
+        .init(init_lifetime)
+        .render(ColorOverLifetimeModifier::new(color_gradient(&[
+            (0.0, Vec4::new(10.0, 10.0, 12.0, 1.0)),
+            (1.0, Vec4::new(1.0, 2.0, 8.0, 0.0)),
+        ])))
+        .render(SizeOverLifetimeModifier {
+            gradient: size_gradient(&[(0.0, 0.08), (1.0, 0.02)]),
+            screen_space_size: false,
+        })
+}
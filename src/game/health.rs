@@ -1,5 +1,14 @@
 #![allow(unreachable_code)]
 
+//! Health, damage and death.
+//!
+//! Damage is sent as a [`DamageEvent`] and reduced by the target's [`Resistances`] and [`Armor`]
+//! before it comes off [`Health`]. [`AdjustHp`] changes health as is, that's what healing and
+//! spark charge use. Both are applied in [`HealthSystems`]; systems dealing damage run before it
+//! and systems reacting to [`Damaged`] and [`Died`] after it to see them on the same tick.
+
+use crate::game::pause_controller::PausableSystems;
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
@@ -14,39 +23,195 @@ pub struct Health(pub f32);
 #[require(Health = enforce_exists!(Health))]
 pub struct MaxHealth(pub f32);
 
+#[auto_register_type]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Reflect)]
+pub enum DamageType {
+    Electric,
+    Physical,
+    Fire,
+}
+
+/// Fraction of each damage type that is shrugged off. Negative values are weaknesses.
+#[auto_register_type]
+#[derive(Component, Reflect, Debug, Default, Copy, Clone)]
+#[reflect(Component)]
+pub struct Resistances {
+    pub electric: f32,
+    pub physical: f32,
+    pub fire: f32,
+}
+
+impl Resistances {
+    pub fn against(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Electric => self.electric,
+            DamageType::Physical => self.physical,
+            DamageType::Fire => self.fire,
+        }
+    }
+}
+
+/// Armor that stops half of the physical damage.
+pub const ARMOR_HALVES_AT: f32 = 100.0;
+
+/// Reduces physical damage, more armor has diminishing returns.
+#[auto_register_type]
+#[derive(Component, Reflect, Debug, Default, Copy, Clone)]
+#[reflect(Component)]
+pub struct Armor(pub f32);
+
+/// Changes health by `amount` without any resistances, positive amounts heal.
 #[derive(Event, Debug)]
 pub struct AdjustHp {
     pub target: Entity,
     pub amount: f32,
 }
 
+/// Deals `amount` damage of `damage_type` to `target`, before resistances.
+#[auto_register_type]
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct DamageEvent {
+    pub target: Entity,
+    /// Whoever dealt the damage.
+    pub source: Option<Entity>,
+    pub amount: f32,
+    pub damage_type: DamageType,
+    /// Jumps the damage made along a chain before landing, 0 for a direct hit.
+    pub hops: u32,
+}
+
+/// Damage that got through to `target`.
+#[auto_register_type]
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct Damaged {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    /// After resistances.
+    pub amount: f32,
+    pub damage_type: DamageType,
+    pub hops: u32,
+}
+
+/// `target` ran out of health and is now [`Dead`].
+#[auto_register_type]
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct Died {
+    pub target: Entity,
+    /// Source of the finishing blow, `None` when health ran out some other way.
+    pub killer: Option<Entity>,
+    pub damage_type: Option<DamageType>,
+    /// Chain hops of the finishing blow.
+    pub hops: u32,
+}
+
 #[auto_register_type]
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 pub struct Dead;
 
+/// Applies [`DamageEvent`]s and [`AdjustHp`] in `FixedUpdate`.
+#[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HealthSystems;
+
 // Plugin
 #[auto_plugin(app=app)]
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, handle_adjust_hp);
+    app.configure_sets(FixedUpdate, HealthSystems.in_set(PausableSystems));
+    app.add_systems(FixedUpdate, apply_health_changes.in_set(HealthSystems));
     app.add_event::<AdjustHp>();
 }
 
+/// Damage left of `amount` after `resistances` and `armor`.
+pub fn mitigate(
+    amount: f32,
+    damage_type: DamageType,
+    resistances: Option<&Resistances>,
+    armor: Option<&Armor>,
+) -> f32 {
+    let resistance = resistances.map_or(0.0, |resistances| resistances.against(damage_type));
+    let mut amount = amount * (1.0 - resistance.min(1.0));
+    if let (DamageType::Physical, Some(armor)) = (damage_type, armor) {
+        amount *= ARMOR_HALVES_AT / (ARMOR_HALVES_AT + armor.0.max(0.0));
+    }
+    amount.max(0.0)
+}
+
+/// `entity` or its closest ancestor with [`Health`], hitting part of something (the wizard on
+/// the tower, a collider on an enemy) hurts the whole.
+pub fn health_owner(
+    entity: Entity,
+    parents: &Query<&ChildOf>,
+    healths: &Query<(), With<Health>>,
+) -> Option<Entity> {
+    std::iter::once(entity)
+        .chain(parents.iter_ancestors(entity))
+        .find(|&entity| healths.contains(entity))
+}
+
 // Internals
 
-fn handle_adjust_hp(
+fn apply_health_changes(
     mut commands: Commands,
-    mut damage_reader: EventReader<AdjustHp>,
-    mut health_query: Query<&mut Health, Without<Dead>>,
+    mut damage_events: EventReader<DamageEvent>,
+    mut adjust_hp_events: EventReader<AdjustHp>,
+    mut targets: Query<
+        (
+            &mut Health,
+            Option<&MaxHealth>,
+            Option<&Resistances>,
+            Option<&Armor>,
+        ),
+        Without<Dead>,
+    >,
+    mut damaged: EventWriter<Damaged>,
+    mut died: EventWriter<Died>,
 ) {
-    for AdjustHp { target, amount } in damage_reader.read() {
-        let Ok(mut health) = health_query.get_mut(*target) else {
+    // `Dead` is only inserted later, don't let a target die twice in the meantime.
+    let mut killed = HashSet::new();
+    let hits = damage_events
+        .read()
+        .map(|event| (event.target, -event.amount, Some(*event)));
+    let adjustments = adjust_hp_events
+        .read()
+        .map(|event| (event.target, event.amount, None));
+    for (target, amount, damage) in hits.chain(adjustments) {
+        if killed.contains(&target) {
+            continue;
+        }
+        let Ok((mut health, max_health, resistances, armor)) = targets.get_mut(target) else {
             continue;
         };
-        health.0 += amount;
-
+        let amount = match damage {
+            Some(event) => {
+                let amount = mitigate(event.amount, event.damage_type, resistances, armor);
+                if amount <= 0.0 {
+                    continue;
+                }
+                damaged.write(Damaged {
+                    target,
+                    source: event.source,
+                    amount,
+                    damage_type: event.damage_type,
+                    hops: event.hops,
+                });
+                -amount
+            }
+            None => amount,
+        };
+        // Healing stops at max health.
+        health.0 = (health.0 + amount).min(max_health.map_or(f32::INFINITY, |max| max.0));
         if health.0 <= 0.0 {
-            commands.entity(*target).insert(Dead);
+            killed.insert(target);
+            commands.entity(target).insert(Dead);
+            died.write(Died {
+                target,
+                killer: damage.and_then(|event| event.source),
+                damage_type: damage.map(|event| event.damage_type),
+                hops: damage.map_or(0, |event| event.hops),
+            });
         }
     }
 }
@@ -56,3 +221,45 @@ impl AdjustHp {
         Self { target, amount }
     }
 }
+
+impl DamageEvent {
+    pub fn new(target: Entity, amount: f32, damage_type: DamageType) -> Self {
+        Self {
+            target,
+            source: None,
+            amount,
+            damage_type,
+            hops: 0,
+        }
+    }
+
+    pub fn with_source(mut self, source: impl Into<Option<Entity>>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    pub fn with_hops(mut self, hops: u32) -> Self {
+        self.hops = hops;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resistances_and_armor() {
+        let resistances = Resistances {
+            electric: -0.5,
+            physical: 0.25,
+            fire: 2.0,
+        };
+        let armor = Armor(ARMOR_HALVES_AT);
+        let mitigated = |damage_type| mitigate(10.0, damage_type, Some(&resistances), Some(&armor));
+        assert_eq!(mitigated(DamageType::Electric), 15.0);
+        assert_eq!(mitigated(DamageType::Physical), 3.75);
+        assert_eq!(mitigated(DamageType::Fire), 0.0);
+        assert_eq!(mitigate(10.0, DamageType::Physical, None, None), 10.0);
+    }
+}
@@ -0,0 +1,74 @@
+//! Gameplay overlay.
+
+use bevy::{prelude::*, ui::Val::*};
+use bevy_auto_plugin::auto_plugin::*;
+
+use crate::game::{
+    pause_controller::UiSystems,
+    rng::seed::{SelectedSeed, format_seed},
+    score::Score,
+    screens::Screen,
+    theme::widget,
+};
+
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Hud;
+
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+struct ScoreLabel;
+
+fn score_text(score: &Score) -> String {
+    match score.combo {
+        0 | 1 => format!("Score: {}", score.points),
+        combo => format!("Score: {} (combo x{combo})", score.points),
+    }
+}
+
+fn spawn_hud(mut commands: Commands, seed: Res<SelectedSeed>, score: Res<Score>) {
+    commands.spawn((
+        Name::new("HUD"),
+        Hud,
+        StateScoped(Screen::Gameplay),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Px(10.0),
+            left: Px(10.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Px(5.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        children![
+            (
+                widget::label(format!("Seed: {}", format_seed(seed.0))),
+                Pickable::IGNORE,
+            ),
+            (
+                widget::label(score_text(&score)),
+                ScoreLabel,
+                Pickable::IGNORE
+            ),
+        ],
+    ));
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_hud);
+    app.add_systems(
+        Update,
+        update_score_label
+            .run_if(resource_changed::<Score>)
+            .in_set(UiSystems),
+    );
+}
+
+fn update_score_label(score: Res<Score>, mut labels: Query<&mut Text, With<ScoreLabel>>) {
+    for mut text in labels.iter_mut() {
+        text.0 = score_text(&score);
+    }
+}
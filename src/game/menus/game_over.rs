@@ -0,0 +1,83 @@
+//! The menu shown when a level ends.
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+use super::level_select::play_level;
+use crate::game::{
+    menus::Menu,
+    rng::seed::{SelectedSeed, format_seed},
+    scenes::{
+        game::LevelRoot,
+        level::{CurrentLevel, Levels},
+        progress::{LevelFailed, LevelProgress},
+    },
+    screens::Screen,
+    theme::widget,
+};
+
+fn spawn_game_over_menu(
+    mut commands: Commands,
+    seed: Res<SelectedSeed>,
+    current_level: Res<CurrentLevel>,
+    levels: Levels,
+    progress: Res<LevelProgress>,
+    outcome: Query<Has<LevelFailed>, With<LevelRoot>>,
+) {
+    let failed = outcome.iter().any(|failed| failed);
+    let next_level = current_level.0 + 1;
+    let has_next_level = !failed && next_level < levels.count() && progress.is_unlocked(next_level);
+    commands
+        .spawn((
+            widget::ui_root("Game Over Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn(widget::header(if failed {
+                "Level failed"
+            } else {
+                "Level complete!"
+            }));
+            parent.spawn(widget::label(format!("Seed: {}", format_seed(seed.0))));
+            if failed {
+                parent.spawn(widget::button("Retry", retry_level));
+            }
+            if has_next_level {
+                parent.spawn(widget::button("Next level", play_next_level));
+            }
+            parent.spawn(widget::button("Select level", open_level_select_menu));
+            parent.spawn(widget::button("Quit to title", quit_to_title));
+        });
+}
+
+fn play_next_level(
+    _: Trigger<Pointer<Click>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let next_level = current_level.0 + 1;
+    play_level(next_level, &mut current_level, &mut next_screen);
+}
+
+fn retry_level(
+    _: Trigger<Pointer<Click>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let level = current_level.0;
+    play_level(level, &mut current_level, &mut next_screen);
+}
+
+fn open_level_select_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::LevelSelect);
+}
+
+fn quit_to_title(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::GameOver), spawn_game_over_menu);
+}
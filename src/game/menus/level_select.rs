@@ -0,0 +1,327 @@
+//! The level select menu, opened from the main menu and after completing a level.
+//!
+//! Also lets the player pick the seed the run is played with.
+
+use bevy::{
+    input::{
+        ButtonState,
+        common_conditions::input_just_pressed,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+    ui::Val::*,
+};
+use bevy_auto_plugin::auto_plugin::*;
+
+use crate::game::{
+    menus::Menu,
+    rng::{
+        SEED_LEN, Seed,
+        seed::{SelectedSeed, daily_seed, format_seed, parse_seed, random_seed},
+    },
+    scenes::{
+        level::{CurrentLevel, LevelAssets, Levels},
+        progress::LevelProgress,
+    },
+    screens::Screen,
+    theme::widget,
+};
+
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+struct LevelSelectRoot;
+
+/// Hex text typed into the seed field, applied to [`SelectedSeed`] whenever it parses.
+#[auto_register_type]
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component)]
+struct SeedInput(String);
+
+const SEED_INPUT_BACKGROUND: Color = Color::srgb(0.1, 0.1, 0.1);
+const SEED_INPUT_INVALID_TEXT: Color = Color::srgb(0.9, 0.3, 0.3);
+
+fn spawn_level_select_menu(
+    mut commands: Commands,
+    existing: Query<Entity, With<LevelSelectRoot>>,
+    levels: Levels,
+    progress: Res<LevelProgress>,
+    seed: Res<SelectedSeed>,
+) {
+    for entity in existing.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands
+        .spawn((
+            widget::ui_root("Level Select Menu"),
+            LevelSelectRoot,
+            GlobalZIndex(2),
+            StateScoped(Menu::LevelSelect),
+        ))
+        .with_children(|parent| {
+            parent.spawn(widget::header("Select Level"));
+            parent.spawn(seed_row(seed.0));
+            parent.spawn(seed_buttons());
+            if levels.count() == 0 {
+                parent.spawn(widget::label("Loading levels..."));
+            }
+            for index in 0..levels.count() {
+                let name = levels.get(index).map_or_else(
+                    || format!("Level {}", index + 1),
+                    |level| level.name.clone(),
+                );
+                if !progress.is_unlocked(index) {
+                    parent.spawn(widget::label(format!("{name} (locked)")));
+                    continue;
+                }
+                parent.spawn(widget::button(
+                    name,
+                    move |_: Trigger<Pointer<Click>>,
+                          mut current_level: ResMut<CurrentLevel>,
+                          mut next_screen: ResMut<NextState<Screen>>| {
+                        play_level(index, &mut current_level, &mut next_screen);
+                    },
+                ));
+            }
+            parent.spawn(widget::button("Back", go_back_on_click));
+        });
+}
+
+/// Builds the level at `index` from scratch, also when a level is being played already.
+/// Goes through the loading screen, which passes straight on once everything is loaded:
+/// setting gameplay while in gameplay doesn't run `OnEnter` again and keeps the old level.
+pub(super) fn play_level(
+    index: usize,
+    current_level: &mut CurrentLevel,
+    next_screen: &mut NextState<Screen>,
+) {
+    current_level.0 = index;
+    next_screen.set(Screen::Loading);
+}
+
+fn seed_row(seed: Seed) -> impl Bundle {
+    (
+        Name::new("Seed Row"),
+        Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Px(20.0),
+            ..default()
+        },
+        children![
+            widget::label("Seed"),
+            (
+                Name::new("Seed Input"),
+                SeedInput(format_seed(seed)),
+                Node {
+                    width: Px(300.0),
+                    height: Px(50.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                BackgroundColor(SEED_INPUT_BACKGROUND),
+                children![(
+                    Name::new("Seed Input Text"),
+                    Text(format_seed(seed)),
+                    TextFont::from_font_size(28.0),
+                    TextColor(Color::WHITE),
+                    Pickable::IGNORE,
+                )],
+            ),
+        ],
+    )
+}
+
+fn seed_buttons() -> impl Bundle {
+    (
+        Name::new("Seed Buttons"),
+        Node {
+            flex_direction: FlexDirection::Row,
+            column_gap: Px(20.0),
+            ..default()
+        },
+        children![
+            widget::button(
+                "Random seed",
+                |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                    commands.trigger(SetSeed(random_seed()));
+                }
+            ),
+            widget::button(
+                "Daily seed",
+                |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                    commands.trigger(SetSeed(daily_seed()));
+                }
+            ),
+        ],
+    )
+}
+
+/// Replaces the seed and the text of the seed field.
+#[derive(Event, Debug, Copy, Clone)]
+struct SetSeed(Seed);
+
+fn on_set_seed(
+    trigger: Trigger<SetSeed>,
+    mut selected: ResMut<SelectedSeed>,
+    mut inputs: Query<&mut SeedInput>,
+) {
+    selected.0 = trigger.event().0;
+    for mut input in inputs.iter_mut() {
+        input.0 = format_seed(selected.0);
+    }
+}
+
+fn edit_seed_input(
+    mut keyboard: EventReader<KeyboardInput>,
+    mut selected: ResMut<SelectedSeed>,
+    mut inputs: Query<&mut SeedInput>,
+) {
+    for event in keyboard.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        for mut input in inputs.iter_mut() {
+            match &event.logical_key {
+                Key::Backspace => {
+                    input.0.pop();
+                }
+                Key::Character(text) => {
+                    for c in text.chars().filter(char::is_ascii_hexdigit) {
+                        if input.0.len() < SEED_LEN * 2 {
+                            input.0.push(c.to_ascii_uppercase());
+                        }
+                    }
+                }
+                _ => continue,
+            }
+            if let Some(seed) = parse_seed(&input.0) {
+                selected.0 = seed;
+            }
+        }
+    }
+}
+
+fn update_seed_input_text(
+    inputs: Query<(&SeedInput, &Children), Changed<SeedInput>>,
+    mut texts: Query<(&mut Text, &mut TextColor)>,
+) {
+    for (input, children) in inputs.iter() {
+        for &child in children.iter() {
+            let Ok((mut text, mut color)) = texts.get_mut(child) else {
+                continue;
+            };
+            text.0 = input.0.clone();
+            color.0 = if parse_seed(&input.0).is_some() {
+                Color::WHITE
+            } else {
+                SEED_INPUT_INVALID_TEXT
+            };
+        }
+    }
+}
+
+fn go_back_on_click(
+    _: Trigger<Pointer<Click>>,
+    screen: Res<State<Screen>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    go_back_inner(&screen, &mut next_screen, &mut next_menu);
+}
+
+fn go_back(
+    screen: Res<State<Screen>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    go_back_inner(&screen, &mut next_screen, &mut next_menu);
+}
+
+fn go_back_inner(
+    screen: &Screen,
+    next_screen: &mut NextState<Screen>,
+    next_menu: &mut NextState<Menu>,
+) {
+    if screen == &Screen::Title {
+        next_menu.set(Menu::Main);
+    } else {
+        next_screen.set(Screen::Title);
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::LevelSelect), spawn_level_select_menu);
+    // Rebuild once the level list is available if the menu was opened while loading.
+    app.add_systems(
+        Update,
+        (
+            spawn_level_select_menu
+                .run_if(in_state(Menu::LevelSelect).and(resource_added::<LevelAssets>)),
+            go_back.run_if(in_state(Menu::LevelSelect).and(input_just_pressed(KeyCode::Escape))),
+            (edit_seed_input, update_seed_input_text)
+                .chain()
+                .run_if(in_state(Menu::LevelSelect)),
+        ),
+    );
+    app.add_observer(on_set_seed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::state::app::StatesPlugin;
+
+    #[derive(Component)]
+    struct BuiltLevel(usize);
+
+    fn select(app: &mut App, index: usize) {
+        app.world_mut()
+            .run_system_once(
+                move |mut current_level: ResMut<CurrentLevel>,
+                      mut next_screen: ResMut<NextState<Screen>>| {
+                    play_level(index, &mut current_level, &mut next_screen);
+                },
+            )
+            .unwrap();
+        // Into loading, then on to gameplay.
+        app.update();
+        app.update();
+    }
+
+    #[test]
+    fn selecting_a_level_during_gameplay_rebuilds_it() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin));
+        app.init_state::<Screen>();
+        app.init_resource::<CurrentLevel>();
+        app.add_systems(
+            OnEnter(Screen::Gameplay),
+            |mut commands: Commands, current_level: Res<CurrentLevel>| {
+                commands.spawn((BuiltLevel(current_level.0), StateScoped(Screen::Gameplay)));
+            },
+        );
+        // Stands in for the loading screen with everything loaded.
+        app.add_systems(
+            OnEnter(Screen::Loading),
+            |mut next_screen: ResMut<NextState<Screen>>| next_screen.set(Screen::Gameplay),
+        );
+
+        select(&mut app, 0);
+        select(&mut app, 2);
+        assert_eq!(
+            *app.world().resource::<State<Screen>>().get(),
+            Screen::Gameplay
+        );
+        let built = app
+            .world_mut()
+            .query::<&BuiltLevel>()
+            .iter(app.world())
+            .map(|level| level.0)
+            .collect::<Vec<_>>();
+        assert_eq!(built, vec![2]);
+    }
+}
@@ -1,6 +1,8 @@
 //! The game's menus and transitions between them.
 
 mod credits;
+mod game_over;
+mod level_select;
 mod main;
 mod pause;
 mod settings;
@@ -17,14 +19,18 @@ pub enum Menu {
     None,
     Main,
     Credits,
+    LevelSelect,
     Settings,
     Pause,
+    GameOver,
 }
 
 #[auto_plugin(app=app)]
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         credits::plugin,
+        game_over::plugin,
+        level_select::plugin,
         main::plugin,
         settings::plugin,
         pause::plugin,
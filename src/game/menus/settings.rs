@@ -5,7 +5,9 @@
 use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 use bevy_auto_plugin::auto_plugin::*;
 
-use crate::game::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::game::{
+    effects::combat_text::CombatTextConfig, menus::Menu, screens::Screen, theme::prelude::*,
+};
 
 fn spawn_settings_menu(mut commands: Commands) {
     commands.spawn((
@@ -39,6 +41,14 @@ fn settings_grid() -> impl Bundle {
                 }
             ),
             global_volume_widget(),
+            (
+                widget::label("Damage Numbers"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            combat_text_widget(),
         ],
     )
 }
@@ -66,6 +76,29 @@ fn global_volume_widget() -> impl Bundle {
     )
 }
 
+fn combat_text_widget() -> impl Bundle {
+    (
+        Name::new("Combat Text Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("<", toggle_combat_text),
+            (
+                Name::new("Current Combat Text"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), CombatTextLabel)],
+            ),
+            widget::button_small(">", toggle_combat_text),
+        ],
+    )
+}
+
 const MIN_VOLUME: f32 = 0.0;
 const MAX_VOLUME: f32 = 3.0;
 
@@ -92,6 +125,22 @@ fn update_global_volume_label(
     label.0 = format!("{percent:3.0}%");
 }
 
+fn toggle_combat_text(_: Trigger<Pointer<Click>>, mut config: ResMut<CombatTextConfig>) {
+    config.enabled = !config.enabled;
+}
+
+#[auto_register_type]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CombatTextLabel;
+
+fn update_combat_text_label(
+    config: Res<CombatTextConfig>,
+    mut label: Single<&mut Text, With<CombatTextLabel>>,
+) {
+    label.0 = if config.enabled { "On" } else { "Off" }.to_string();
+}
+
 fn go_back_on_click(
     _: Trigger<Pointer<Click>>,
     screen: Res<State<Screen>>,
@@ -122,6 +171,6 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (update_global_volume_label, update_combat_text_label).run_if(in_state(Menu::Settings)),
     );
 }
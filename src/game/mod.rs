@@ -4,6 +4,8 @@ mod enforce_exists;
 mod asset_tracking;
 mod audio;
 pub mod behaviors;
+#[cfg(feature = "bench")]
+pub mod bench;
 mod camera;
 mod constants;
 mod despawn;
@@ -12,16 +14,22 @@ mod dev;
 mod effects;
 mod game_system_set;
 mod health;
+mod hud;
 mod menus;
 mod pause_controller;
 mod physics;
 mod prefabs;
 mod rng;
 mod scenes;
+mod score;
 pub mod screens;
+mod simulation;
 mod snapshot;
 mod spark;
+pub mod spatial;
+mod terrain;
 mod theme;
+mod time_scale;
 
 use crate::game::rng::RngPlugin;
 use bevy::app::PluginGroupBuilder;
@@ -61,15 +69,21 @@ impl Plugin for GamePlugin {
         app.add_plugins(asset_tracking::plugin);
         app.add_plugins(pause_controller::plugin);
         app.add_plugins(physics::plugin);
+        app.add_plugins(simulation::plugin);
+        app.add_plugins(time_scale::plugin);
+        app.add_plugins(spatial::plugin);
         app.add_plugins(prefabs::plugin);
         app.add_plugins(behaviors::plugin);
         app.add_plugins(effects::plugin);
         app.add_plugins(scenes::plugin);
+        app.add_plugins(terrain::plugin);
         app.add_plugins(audio::plugin);
         app.add_plugins(theme::plugin);
         app.add_plugins(menus::plugin);
         app.add_plugins(screens::plugin);
         app.add_plugins(health::plugin);
+        app.add_plugins(score::plugin);
+        app.add_plugins(hud::plugin);
         app.add_plugins(spark::plugin);
         app.add_plugins(despawn::plugin::<PreUpdate>);
     }
@@ -8,12 +8,145 @@ use bevy_auto_plugin::auto_plugin::*;
 #[states(scoped_entities)]
 pub(super) struct Pause(pub bool);
 
-/// A system set for systems that shouldn't run while the game is paused.
+// Systems running during gameplay go into one of the sets below, picked by what they touch
+// rather than by which schedule they are in. Menus never need to pause and stay out of them.
+
+/// Gameplay: anything that changes the state of the game (movement, damage, spawning, AI).
+/// Frozen while paused, runs for a single frame on a [`FrameStep`], which is exactly one fixed
+/// tick for the systems in `FixedUpdate`.
 #[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub(super) struct PausableSystems;
 
+/// Effects that only change how the game looks (bolts, particles, screen shake) and never feed
+/// back into gameplay. Frozen together with gameplay so a paused frame holds still, GPU particles
+/// already simulated hold still because they run on virtual time.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub(super) struct CosmeticSystems;
+
+/// Camera, HUD and debug overlays. Never paused, so anything timed in here should use
+/// [`Time<Real>`] to keep moving while virtual time stands still, which [`Pause`] makes it do.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub(super) struct UiSystems;
+
+/// Lets paused gameplay run for a single frame that advances virtual time by one fixed timestep.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub(super) struct FrameStep {
+    /// Step on the next frame.
+    pub requested: bool,
+    /// This frame is a step.
+    pub active: bool,
+}
+
+/// Whether [`PausableSystems`] and [`CosmeticSystems`] run this frame.
+pub(super) fn gameplay_running(pause: Res<State<Pause>>, step: Res<FrameStep>) -> bool {
+    !pause.0 || step.active
+}
+
+fn begin_frame_step(mut step: ResMut<FrameStep>) {
+    step.active = std::mem::take(&mut step.requested);
+}
+
+/// Virtual time stands still while paused, which stops the fixed loop and with it physics.
+fn pause_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// Advances paused virtual time by exactly one fixed timestep, so the fixed loop runs once.
+///
+/// Virtual time has already been updated this frame with a delta of zero. The fixed loop
+/// accumulates whatever delta it sees, and the overstep left over is always less than a
+/// timestep, so adding one timestep runs exactly one tick.
+fn step_virtual_time(
+    pause: Res<State<Pause>>,
+    step: Res<FrameStep>,
+    fixed_time: Res<Time<Fixed>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if pause.0 && step.active && virtual_time.is_paused() {
+        virtual_time.advance_by(fixed_time.timestep());
+    }
+}
+
+fn end_frame_step(mut step: ResMut<FrameStep>) {
+    step.active = false;
+}
+
+#[cfg(feature = "dev")]
+fn request_frame_step(mut step: ResMut<FrameStep>) {
+    step.requested = true;
+}
+
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
-    app.configure_sets(Update, PausableSystems.run_if(in_state(Pause(false))));
-    app.configure_sets(PostUpdate, PausableSystems.run_if(in_state(Pause(false))));
+    app.configure_sets(Update, PausableSystems.run_if(gameplay_running));
+    app.configure_sets(FixedUpdate, PausableSystems.run_if(gameplay_running));
+    app.configure_sets(PostUpdate, PausableSystems.run_if(gameplay_running));
+    app.configure_sets(Update, CosmeticSystems.run_if(gameplay_running));
+    app.configure_sets(PostUpdate, CosmeticSystems.run_if(gameplay_running));
+    app.add_systems(OnEnter(Pause(true)), pause_virtual_time);
+    app.add_systems(OnExit(Pause(true)), unpause_virtual_time);
+    // Before the fixed loop so a step covers fixed, variable and post update alike.
+    app.add_systems(
+        PreUpdate,
+        (begin_frame_step, step_virtual_time.after(begin_frame_step)),
+    );
+    app.add_systems(Last, end_frame_step);
+    #[cfg(feature = "dev")]
+    app.add_systems(
+        Update,
+        request_frame_step
+            .run_if(
+                in_state(Pause(true)).and(bevy::input::common_conditions::input_just_pressed(
+                    KeyCode::Period,
+                )),
+            )
+            .in_set(UiSystems),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::state::app::StatesPlugin;
+
+    #[derive(Resource, Default)]
+    struct Ticks(u32);
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, plugin));
+        app.init_resource::<Ticks>();
+        app.add_systems(
+            FixedUpdate,
+            (|mut ticks: ResMut<Ticks>| ticks.0 += 1).in_set(PausableSystems),
+        );
+        app.world_mut()
+            .resource_mut::<NextState<Pause>>()
+            .set(Pause(true));
+        app.update();
+        app
+    }
+
+    #[test]
+    fn frame_step_runs_one_tick() {
+        let mut app = test_app();
+        for _ in 0..3 {
+            std::thread::sleep(Time::<Fixed>::default().timestep());
+            app.update();
+        }
+        assert_eq!(app.world().resource::<Ticks>().0, 0);
+
+        for step in 1..=3 {
+            app.world_mut().resource_mut::<FrameStep>().requested = true;
+            app.update();
+            assert_eq!(app.world().resource::<Ticks>().0, step);
+        }
+    }
 }
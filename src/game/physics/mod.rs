@@ -1,8 +1,5 @@
-use crate::game::pause_controller::Pause;
-use avian3d::prelude::{
-    Physics, PhysicsInterpolationPlugin, PhysicsPickingPlugin, PhysicsPlugins, PhysicsTime,
-};
 use avian3d::prelude::{PhysicsDebugPlugin, PhysicsGizmos};
+use avian3d::prelude::{PhysicsInterpolationPlugin, PhysicsPickingPlugin, PhysicsPlugins};
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
@@ -28,7 +25,10 @@ fn toggle_gizmos(
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
-    app.add_plugins(PhysicsPlugins::default().set(PhysicsInterpolationPlugin::extrapolate_all()));
+    // Gameplay moves bodies on the fixed timestep as well, so ease between ticks when rendering.
+    // Physics runs in the fixed loop, so pausing virtual time pauses it and a frame step is
+    // exactly one physics step.
+    app.add_plugins(PhysicsPlugins::default().set(PhysicsInterpolationPlugin::interpolate_all()));
     app.add_plugins(PhysicsPickingPlugin);
     app.add_plugins(PhysicsDebugPlugin::default());
     app.world_mut()
@@ -36,11 +36,5 @@ pub(crate) fn plugin(app: &mut App) {
         .config_mut::<PhysicsGizmos>()
         .0
         .enabled = app.world().resource::<PhysicsDebugGizmosEnabled>().0;
-    app.add_systems(OnEnter(Pause(false)), |mut time: ResMut<Time<Physics>>| {
-        time.unpause();
-    });
-    app.add_systems(OnEnter(Pause(true)), |mut time: ResMut<Time<Physics>>| {
-        time.pause();
-    });
     app.add_systems(Update, toggle_gizmos);
 }
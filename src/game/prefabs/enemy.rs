@@ -4,8 +4,14 @@ use crate::game::asset_tracking::LoadResource;
 use avian3d::prelude::{Collider, LockedAxes, RigidBody};
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
+use serde::{Deserialize, Serialize};
 
 use crate::game::behaviors::MovementSpeed;
+use crate::game::behaviors::brain::{Brain, BrainAssets};
+use crate::game::behaviors::flocking::Flocking;
+use crate::game::behaviors::ranged_attack::RangedAttack;
+use crate::game::health::{Armor, Health, MaxHealth, Resistances};
+use crate::game::spatial::SpatialIndexed;
 
 #[auto_register_type]
 #[derive(Resource, Asset, Debug, Clone, Reflect)]
@@ -25,17 +31,64 @@ impl FromWorld for EnemyAssets {
 
 #[auto_register_type]
 #[auto_name]
-#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 #[reflect(Component)]
-#[require(Transform)]
+#[require(Transform, SpatialIndexed)]
 pub enum Enemy {
     BaseSkele,
+    /// Keeps its distance and shoots projectiles.
+    SkeleArcher,
 }
 
 impl Enemy {
     pub fn default_move_speed(&self) -> f32 {
         match self {
             Self::BaseSkele => 8.0,
+            Self::SkeleArcher => 6.0,
+        }
+    }
+
+    /// How close the enemy walks up to its target before attacking.
+    pub fn attack_distance(&self) -> f32 {
+        match self {
+            Self::BaseSkele => 20.0,
+            Self::SkeleArcher => 150.0,
+        }
+    }
+
+    /// Damage dealt while attacking up close.
+    pub fn melee_damage_per_second(&self) -> f32 {
+        match self {
+            Self::BaseSkele => 5.0,
+            Self::SkeleArcher => 2.0,
+        }
+    }
+
+    pub fn max_health(&self) -> f32 {
+        match self {
+            Self::BaseSkele => 30.0,
+            Self::SkeleArcher => 15.0,
+        }
+    }
+
+    pub fn resistances(&self) -> Resistances {
+        match self {
+            // Bones don't burn well.
+            Self::BaseSkele => Resistances {
+                fire: 0.5,
+                ..default()
+            },
+            Self::SkeleArcher => Resistances {
+                electric: -0.25,
+                ..default()
+            },
+        }
+    }
+
+    pub fn armor(&self) -> Armor {
+        match self {
+            Self::BaseSkele => Armor(25.0),
+            Self::SkeleArcher => Armor(0.0),
         }
     }
 }
@@ -50,6 +103,7 @@ fn on_enemy_added(
     trigger: Trigger<OnAdd, Enemy>,
     query: Query<&Enemy>,
     enemy_assets: Res<EnemyAssets>,
+    brain_assets: Res<BrainAssets>,
     gltfs: Res<Assets<Gltf>>,
     mut commands: Commands,
 ) {
@@ -59,7 +113,7 @@ fn on_enemy_added(
 
     // Model handle
     let gltf_h = match *enemy {
-        Enemy::BaseSkele => enemy_assets.base_skele.clone(),
+        Enemy::BaseSkele | Enemy::SkeleArcher => enemy_assets.base_skele.clone(),
     };
     let gltf = gltfs
         .get(&gltf_h)
@@ -68,6 +122,12 @@ fn on_enemy_added(
     // MovementSpeed
     let movement_speed = MovementSpeed(enemy.default_move_speed());
 
+    // Behavior tree
+    let tree = match *enemy {
+        Enemy::BaseSkele => brain_assets.melee.clone(),
+        Enemy::SkeleArcher => brain_assets.ranged.clone(),
+    };
+
     commands.entity(trigger.target()).insert((
         children![
             (
@@ -88,5 +148,16 @@ fn on_enemy_added(
         RigidBody::Kinematic,
         LockedAxes::ROTATION_LOCKED,
         movement_speed,
+        Brain::new(tree),
+        Flocking::default(),
+        Health(enemy.max_health()),
+        MaxHealth(enemy.max_health()),
+        enemy.resistances(),
+        enemy.armor(),
     ));
+    if *enemy == Enemy::SkeleArcher {
+        commands
+            .entity(trigger.target())
+            .insert(RangedAttack::default());
+    }
 }
@@ -0,0 +1,205 @@
+//! Dead enemies are parked in the [`EnemyPool`] and brought back for later spawns instead of
+//! building a new model, colliders and brain every time.
+//!
+//! The pool is filled while loading so the first waves don't pay for it either. Pooled enemies
+//! are [`InEnemyPool`], hidden and have their physics disabled. Gameplay systems only look at
+//! enemies [`InPlay`], so they skip them just like dead ones.
+
+use super::enemy::Enemy;
+use crate::game::asset_tracking::ResourceHandles;
+use crate::game::behaviors::brain::{Brain, Intent};
+use crate::game::behaviors::flocking::Flocking;
+use crate::game::behaviors::ranged_attack::RangedAttack;
+use crate::game::behaviors::target_ent::TargetEnt;
+use crate::game::behaviors::target_select::TargetSelector;
+use crate::game::health::{Dead, Health, MaxHealth};
+use crate::game::screens::Screen;
+use avian3d::prelude::{Collider, ColliderDisabled, RigidBodyDisabled};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct EnemyPoolConfig {
+    /// Enemies of every kind built up front while loading.
+    #[default(32)]
+    pub prewarm_per_kind: usize,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct EnemyPool {
+    free: HashMap<Enemy, Vec<Entity>>,
+    prewarmed: bool,
+}
+
+/// An enemy waiting in the [`EnemyPool`] to be spawned again.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct InEnemyPool;
+
+/// Filter for enemies that are in play, neither [`Dead`] nor waiting in the [`EnemyPool`].
+pub type InPlay = (Without<Dead>, Without<InEnemyPool>);
+
+/// What every enemy is scaled by.
+// TODO scale should be set in the enemy spawner
+const ENEMY_SCALE: f32 = 15.0;
+
+impl EnemyPool {
+    pub fn free(&self, enemy: Enemy) -> usize {
+        self.free.get(&enemy).map_or(0, Vec::len)
+    }
+
+    /// Spawns `enemy` at `translation`, reusing a pooled one if there is any.
+    pub fn spawn(&mut self, commands: &mut Commands, enemy: Enemy, translation: Vec3) -> Entity {
+        let transform =
+            Transform::from_translation(translation).with_scale(Vec3::splat(ENEMY_SCALE));
+        let Some(entity) = self.free.get_mut(&enemy).and_then(Vec::pop) else {
+            return commands
+                .spawn((
+                    Name::new("Skele"),
+                    enemy,
+                    transform,
+                    TargetSelector::default(),
+                ))
+                .id();
+        };
+        commands
+            .entity(entity)
+            .remove::<(InEnemyPool, Dead, RigidBodyDisabled)>()
+            .insert((transform, Visibility::Inherited, TargetSelector::default()));
+        entity
+    }
+}
+
+pub fn enemy_pool_prewarmed(pool: Res<EnemyPool>) -> bool {
+    pool.prewarmed
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<EnemyPool>();
+    app.add_observer(on_enter_pool);
+    app.add_observer(on_leave_pool);
+    app.add_observer(on_enemy_died);
+    app.add_systems(
+        Update,
+        prewarm.run_if(in_state(Screen::Loading).and(not(enemy_pool_prewarmed))),
+    );
+    // Loading is skipped when the assets are already there.
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        prewarm.run_if(not(enemy_pool_prewarmed)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), park_all);
+}
+
+fn prewarm(
+    mut commands: Commands,
+    config: Res<EnemyPoolConfig>,
+    resource_handles: Res<ResourceHandles>,
+    mut pool: ResMut<EnemyPool>,
+) {
+    if !resource_handles.is_all_done() {
+        return;
+    }
+    pool.prewarmed = true;
+    for enemy in [Enemy::BaseSkele, Enemy::SkeleArcher] {
+        for _ in pool.free(enemy)..config.prewarm_per_kind {
+            // Built like any other enemy first, then parked.
+            commands
+                .spawn((
+                    Name::new("Skele"),
+                    enemy,
+                    Transform::from_scale(Vec3::splat(ENEMY_SCALE)),
+                ))
+                .insert(park());
+        }
+    }
+}
+
+fn park() -> impl Bundle {
+    (InEnemyPool, Visibility::Hidden, RigidBodyDisabled)
+}
+
+fn on_enemy_died(
+    trigger: Trigger<OnAdd, Dead>,
+    mut commands: Commands,
+    enemies: Query<(), (With<Enemy>, Without<InEnemyPool>)>,
+) {
+    if enemies.contains(trigger.target()) {
+        commands.entity(trigger.target()).insert(park());
+    }
+}
+
+/// Enemies are not part of the level, so they have to be put away when it goes.
+fn park_all(mut commands: Commands, enemies: Query<Entity, (With<Enemy>, Without<InEnemyPool>)>) {
+    for entity in enemies.iter() {
+        commands.entity(entity).insert(park());
+    }
+}
+
+fn on_enter_pool(
+    trigger: Trigger<OnAdd, InEnemyPool>,
+    mut commands: Commands,
+    mut pool: ResMut<EnemyPool>,
+    enemies: Query<(&Enemy, Option<&Children>)>,
+    colliders: Query<(), With<Collider>>,
+) {
+    let entity = trigger.target();
+    let Ok((enemy, children)) = enemies.get(entity) else {
+        return;
+    };
+    pool.free.entry(*enemy).or_default().push(entity);
+    commands.entity(entity).remove::<(TargetEnt, ChildOf)>();
+    for &child in children.into_iter().flatten() {
+        if colliders.contains(child) {
+            commands.entity(child).insert(ColliderDisabled);
+        }
+    }
+}
+
+/// Puts a reused enemy back into the state it was first spawned in.
+fn on_leave_pool(
+    trigger: Trigger<OnRemove, InEnemyPool>,
+    mut commands: Commands,
+    mut enemies: Query<(
+        Option<&Children>,
+        Option<(&mut Health, &MaxHealth)>,
+        Option<&mut Brain>,
+        Option<&mut Intent>,
+        Option<&mut Flocking>,
+        Option<&mut RangedAttack>,
+    )>,
+    colliders: Query<(), With<Collider>>,
+) {
+    let entity = trigger.target();
+    let Ok((children, health, brain, intent, flocking, ranged_attack)) = enemies.get_mut(entity)
+    else {
+        return;
+    };
+    for &child in children.into_iter().flatten() {
+        if colliders.contains(child) {
+            commands.entity(child).remove::<ColliderDisabled>();
+        }
+    }
+    if let Some((mut health, max_health)) = health {
+        health.0 = max_health.0;
+    }
+    if let Some(mut brain) = brain {
+        brain.reset();
+    }
+    if let Some(mut intent) = intent {
+        *intent = Intent::default();
+    }
+    if let Some(mut flocking) = flocking {
+        *flocking = Flocking::default();
+    }
+    if let Some(mut ranged_attack) = ranged_attack {
+        ranged_attack.cooldown.reset();
+    }
+}
@@ -1,4 +1,7 @@
 pub mod enemy;
+pub mod enemy_pool;
+pub mod obstacle;
+pub mod projectile;
 pub mod spawner;
 pub mod tower;
 pub mod wizard;
@@ -9,6 +12,9 @@ use bevy_auto_plugin::auto_plugin::*;
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
     app.add_plugins(enemy::plugin);
+    app.add_plugins(enemy_pool::plugin);
+    app.add_plugins(obstacle::plugin);
+    app.add_plugins(projectile::plugin);
     app.add_plugins(spawner::plugin);
     app.add_plugins(tower::plugin);
     app.add_plugins(wizard::plugin);
@@ -0,0 +1,42 @@
+use avian3d::prelude::{Collider, RigidBody};
+use bevy::color::palettes::css::DARK_SLATE_GRAY;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+
+/// Static box blocking movement.
+#[auto_register_type]
+#[auto_name]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct Obstacle {
+    pub size: Vec3,
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_observer(on_obstacle_inserted);
+}
+
+fn on_obstacle_inserted(
+    trigger: Trigger<OnInsert, Obstacle>,
+    obstacles: Query<&Obstacle>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let obstacle = obstacles
+        .get(trigger.target())
+        .expect("No target entity for trigger");
+    let size = obstacle.size;
+    commands.entity(trigger.target()).insert((
+        Mesh3d(meshes.add(Cuboid::from_size(size))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::from(DARK_SLATE_GRAY),
+            perceptual_roughness: 1.0,
+            ..Default::default()
+        })),
+        Collider::cuboid(size.x, size.y, size.z),
+        RigidBody::Static,
+    ));
+}
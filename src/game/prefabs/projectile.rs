@@ -0,0 +1,307 @@
+//! Physics driven projectiles, fired through [`FireProjectile`].
+//!
+//! Projectiles that hit something are parked in a pool and reused by the next shot, only once
+//! the pool is full they are despawned. Ones that fly for their whole lifetime are despawned
+//! like any other timed entity.
+
+use crate::game::behaviors::target_select::Provoked;
+use crate::game::despawn::DespawnDelayed;
+use crate::game::effects::lightning_ball::LightningBallConduit;
+use crate::game::health::{DamageEvent, DamageType, Health, health_owner};
+use crate::game::pause_controller::PausableSystems;
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::prefabs::enemy_pool::InPlay;
+use crate::game::scenes::game::LevelRoot;
+use avian3d::prelude::{
+    Collider, CollisionEventsEnabled, Gravity, LinearVelocity, OnCollisionStart, RigidBody, Sensor,
+};
+use bevy::color::palettes::css::ORANGE_RED;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ProjectileConfig {
+    #[default(1.5)]
+    pub radius: f32,
+    #[default(6.0)]
+    pub lifetime_secs: f32,
+    /// Spent projectiles kept around for reuse.
+    #[default(64)]
+    pub max_pooled: usize,
+    /// How often a projectile can be redirected by conduits.
+    #[default(2)]
+    pub max_redirects: u32,
+    /// Speed of a redirected projectile.
+    #[default(120.0)]
+    pub redirect_speed: f32,
+}
+
+/// Who a projectile hurts.
+#[auto_register_type]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Reflect)]
+pub enum ProjectileTeam {
+    /// Fired by enemies, hurts everything but enemies.
+    Enemy,
+    /// Turned around by a conduit, only hurts enemies.
+    Defender,
+}
+
+#[auto_register_type]
+#[auto_name]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Projectile {
+    pub damage: f32,
+    pub team: ProjectileTeam,
+    /// Entity that fired it, never hit by its own shot.
+    pub source: Option<Entity>,
+    pub redirects_left: u32,
+}
+
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+struct ProjectileLifetime(Timer);
+
+/// A spent projectile waiting to be fired again.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+struct Pooled;
+
+#[auto_register_type]
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct FireProjectile {
+    pub origin: Vec3,
+    pub velocity: Vec3,
+    pub damage: f32,
+    pub team: ProjectileTeam,
+    pub source: Option<Entity>,
+}
+
+#[auto_register_type]
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+struct ProjectileAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for ProjectileAssets {
+    fn from_world(world: &mut World) -> Self {
+        let radius = world.get_resource_or_init::<ProjectileConfig>().radius;
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Sphere::new(radius));
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: ORANGE_RED.into(),
+                emissive: LinearRgba::from(ORANGE_RED) * 4.0,
+                ..Default::default()
+            });
+        Self { mesh, material }
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<ProjectileAssets>();
+    app.add_observer(on_projectile_hit);
+    app.add_systems(
+        FixedUpdate,
+        (fire_projectiles, expire_projectiles).in_set(PausableSystems),
+    );
+}
+
+/// Launch velocity that lands a shot from `from` on `to` after `flight_secs` under `gravity`.
+pub fn ballistic_velocity(from: Vec3, to: Vec3, flight_secs: f32, gravity: Vec3) -> Vec3 {
+    let flight_secs = flight_secs.max(f32::EPSILON);
+    (to - from) / flight_secs - gravity * flight_secs / 2.0
+}
+
+fn fire_projectiles(
+    mut commands: Commands,
+    mut events: EventReader<FireProjectile>,
+    config: Res<ProjectileConfig>,
+    assets: Res<ProjectileAssets>,
+    level_root: Option<Single<Entity, With<LevelRoot>>>,
+    pooled: Query<Entity, With<Pooled>>,
+) {
+    let Some(level_root) = level_root else {
+        events.clear();
+        return;
+    };
+    let mut pooled = pooled.iter();
+    for event in events.read() {
+        let entity = match pooled.next() {
+            Some(entity) => entity,
+            None => commands.spawn(ChildOf(*level_root)).id(),
+        };
+        commands.entity(entity).remove::<Pooled>().insert((
+            Projectile {
+                damage: event.damage,
+                team: event.team,
+                source: event.source,
+                redirects_left: config.max_redirects,
+            },
+            ProjectileLifetime(Timer::from_seconds(config.lifetime_secs, TimerMode::Once)),
+            Transform::from_translation(event.origin),
+            Visibility::Inherited,
+            Mesh3d(assets.mesh.clone()),
+            MeshMaterial3d(assets.material.clone()),
+            Pickable::IGNORE,
+            RigidBody::Dynamic,
+            Collider::sphere(config.radius),
+            Sensor,
+            CollisionEventsEnabled,
+            LinearVelocity(event.velocity),
+        ));
+    }
+}
+
+fn expire_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut projectiles: Query<(Entity, &mut ProjectileLifetime), Without<Pooled>>,
+) {
+    for (entity, mut lifetime) in projectiles.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(entity).trigger(DespawnDelayed);
+        }
+    }
+}
+
+/// Parks the projectile in the pool, or despawns it if the pool is full.
+fn retire(commands: &mut Commands, entity: Entity, pool_size: &mut usize, max_pooled: usize) {
+    if *pool_size >= max_pooled {
+        commands.entity(entity).trigger(DespawnDelayed);
+        return;
+    }
+    *pool_size += 1;
+    commands
+        .entity(entity)
+        .remove::<(
+            Projectile,
+            ProjectileLifetime,
+            RigidBody,
+            Collider,
+            Sensor,
+            CollisionEventsEnabled,
+            LinearVelocity,
+        )>()
+        .insert((Pooled, Visibility::Hidden));
+}
+
+fn on_projectile_hit(
+    trigger: Trigger<OnCollisionStart>,
+    mut commands: Commands,
+    config: Res<ProjectileConfig>,
+    gravity: Res<Gravity>,
+    mut projectiles: Query<(&mut Projectile, &GlobalTransform, &mut LinearVelocity)>,
+    sensors: Query<(), With<Sensor>>,
+    conduits: Query<(), With<LightningBallConduit>>,
+    enemies: Query<&GlobalTransform, (With<Enemy>, InPlay)>,
+    parents: Query<&ChildOf>,
+    healths: Query<(), With<Health>>,
+    pooled: Query<(), With<Pooled>>,
+    mut damage: EventWriter<DamageEvent>,
+) {
+    let projectile_entity = trigger.target();
+    let Ok((mut projectile, transform, mut velocity)) = projectiles.get_mut(projectile_entity)
+    else {
+        return;
+    };
+    let collider = trigger.collider;
+    let hit = trigger.body.unwrap_or(collider);
+    // Other projectiles and trigger volumes are flown through.
+    if sensors.contains(collider) || Some(hit) == projectile.source {
+        return;
+    }
+
+    if conduits.contains(collider) && projectile.redirects_left > 0 {
+        // Chain reaction: conduits send the shot on into the closest enemy.
+        let origin = transform.translation();
+        let nearest = enemies
+            .iter()
+            .map(GlobalTransform::translation)
+            .min_by(|a, b| {
+                a.distance_squared(origin)
+                    .total_cmp(&b.distance_squared(origin))
+            });
+        velocity.0 = match nearest {
+            Some(target) => {
+                let flight_secs = origin.distance(target) / config.redirect_speed;
+                ballistic_velocity(origin, target, flight_secs, gravity.0)
+            }
+            None => -velocity.0,
+        };
+        projectile.team = ProjectileTeam::Defender;
+        projectile.redirects_left -= 1;
+        projectile.source = Some(collider);
+        return;
+    }
+
+    let is_enemy = enemies.contains(hit);
+    // Whatever owns the health of the part that got hit takes the damage.
+    let victim = health_owner(hit, &parents, &healths);
+    match (projectile.team, victim) {
+        (ProjectileTeam::Enemy, _) if is_enemy => return,
+        (ProjectileTeam::Defender, _) if !is_enemy => {}
+        (_, None) => {}
+        (ProjectileTeam::Defender, Some(victim)) => {
+            // Redirected shots carry the conduit's charge.
+            damage.write(
+                DamageEvent::new(victim, projectile.damage, DamageType::Electric)
+                    .with_source(projectile.source)
+                    .with_hops(
+                        config
+                            .max_redirects
+                            .saturating_sub(projectile.redirects_left),
+                    ),
+            );
+            if let Some(source) = projectile.source {
+                commands.entity(victim).trigger(Provoked { source });
+            }
+        }
+        (ProjectileTeam::Enemy, Some(victim)) => {
+            damage.write(
+                DamageEvent::new(victim, projectile.damage, DamageType::Physical)
+                    .with_source(projectile.source),
+            );
+        }
+    }
+    let mut pool_size = pooled.iter().count();
+    retire(
+        &mut commands,
+        projectile_entity,
+        &mut pool_size,
+        config.max_pooled,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ballistic_shot_lands_on_target() {
+        let gravity = Vec3::NEG_Y * 9.81;
+        let from = Vec3::new(0.0, 30.0, 0.0);
+        let to = Vec3::new(120.0, 0.0, -50.0);
+        let flight_secs = 2.5;
+        let velocity = ballistic_velocity(from, to, flight_secs, gravity);
+        let landed = from + velocity * flight_secs + gravity * flight_secs * flight_secs / 2.0;
+        assert!(landed.distance(to) < 1e-3);
+        // Without gravity it's a straight line.
+        assert_eq!(
+            ballistic_velocity(from, to, 2.0, Vec3::ZERO),
+            (to - from) / 2.0
+        );
+    }
+}
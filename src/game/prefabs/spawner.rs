@@ -2,25 +2,59 @@ use std::time::Duration;
 
 use bevy::{color::palettes::css::RED, prelude::*};
 use bevy_auto_plugin::auto_plugin::*;
+use rand::Rng;
 
 use super::enemy::Enemy;
+use crate::game::rng::{choice::WeightedTable, stream::GameplayRng};
 
 #[auto_register_type]
 #[auto_name]
-#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[derive(Component, Debug, Clone, Reflect)]
 #[reflect(Component)]
 #[require(Transform)]
+#[require(GameplayRng)]
 pub struct Spawner {
-    /// What enemy will get spawned.
+    /// What enemy will get spawned, unless there is a `mix`.
     pub spawns: Enemy,
+    /// Enemies rolled for each spawn with a probability proportional to their weight.
+    pub mix: Vec<(Enemy, f32)>,
     /// How long it takes to spawn an enemy.
     pub spawn_duration: Duration,
+    /// Delay before the first spawn, as authored in the level.
+    pub initial_delay: Duration,
+    /// Number of entities this spawner creates in total, as authored in the level.
+    pub spawn_count: u32,
     /// Countdown for next spawn.
     pub time_to_next_spawn: Duration,
     /// Number of entities this spawner will create.
     pub spawn_left: u32,
 }
 
+impl Spawner {
+    pub fn new(
+        spawns: Enemy,
+        mix: Vec<(Enemy, f32)>,
+        spawn_duration: Duration,
+        initial_delay: Duration,
+        spawn_count: u32,
+    ) -> Self {
+        Self {
+            spawns,
+            mix,
+            spawn_duration,
+            initial_delay,
+            spawn_count,
+            time_to_next_spawn: initial_delay,
+            spawn_left: spawn_count,
+        }
+    }
+
+    /// Picks the enemy to spawn next. Only draws from `rng` when there is a mix.
+    pub fn next_enemy<R: Rng + ?Sized>(&self, rng: &mut R) -> Enemy {
+        WeightedTable::new(self.mix.iter().copied()).map_or(self.spawns, |table| *table.choose(rng))
+    }
+}
+
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
     app.add_observer(on_spawner_added);
@@ -41,3 +75,32 @@ fn on_spawner_added(
         })),
     ));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::{Prng, ZERO_SEED};
+    use rand::SeedableRng;
+
+    fn spawner(mix: Vec<(Enemy, f32)>) -> Spawner {
+        Spawner::new(Enemy::BaseSkele, mix, Duration::ZERO, Duration::ZERO, 1)
+    }
+
+    #[test]
+    fn without_mix_spawns_the_same_enemy() {
+        let mut rng = Prng::from_seed(ZERO_SEED);
+        assert_eq!(spawner(Vec::new()).next_enemy(&mut rng), Enemy::BaseSkele);
+        // The stream is left alone, so adding the mix field didn't shift existing levels.
+        assert_eq!(
+            rng.random::<u64>(),
+            Prng::from_seed(ZERO_SEED).random::<u64>()
+        );
+    }
+
+    #[test]
+    fn mix_rolls_weighted_enemies() {
+        let mut rng = Prng::from_seed(ZERO_SEED);
+        let spawner = spawner(vec![(Enemy::SkeleArcher, 1.0), (Enemy::BaseSkele, 0.0)]);
+        assert!((0..100).all(|_| spawner.next_enemy(&mut rng) == Enemy::SkeleArcher));
+    }
+}
@@ -3,15 +3,26 @@ use bevy::color::palettes::css::GRAY;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
+use crate::game::behaviors::target_select::DefenseTarget;
+use crate::game::camera::cut::CameraCut;
+use crate::game::health::{Dead, Health, MaxHealth};
+
+const MAX_HEALTH: f32 = 500.0;
+/// How long the camera lingers on the fallen tower.
+const FALL_CUT_HOLD_SECS: f32 = 3.0;
+const FALL_CUT_RADIUS: f32 = 300.0;
+
 #[auto_register_type]
 #[auto_name]
 #[derive(Component, Debug, Default, Copy, Clone, Reflect)]
 #[reflect(Component)]
+#[require(DefenseTarget)]
 pub struct Tower;
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
     app.add_observer(on_tower_added);
+    app.add_observer(on_tower_fallen);
 }
 
 fn on_tower_added(
@@ -31,5 +42,20 @@ fn on_tower_added(
         })),
         Collider::cylinder(RADIUS, HEIGHT),
         RigidBody::Static,
+        MaxHealth(MAX_HEALTH),
+        Health(MAX_HEALTH),
     ));
 }
+
+fn on_tower_fallen(
+    trigger: Trigger<OnAdd, Dead>,
+    towers: Query<&GlobalTransform, With<Tower>>,
+    mut camera_cuts: EventWriter<CameraCut>,
+) {
+    let Ok(transform) = towers.get(trigger.target()) else {
+        return;
+    };
+    camera_cuts.write(
+        CameraCut::new(transform.translation(), FALL_CUT_HOLD_SECS).with_radius(FALL_CUT_RADIUS),
+    );
+}
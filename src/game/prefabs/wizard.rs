@@ -1,4 +1,5 @@
 use crate::game::asset_tracking::LoadResource;
+use crate::game::behaviors::target_select::DefenseTarget;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
@@ -22,6 +23,7 @@ impl FromWorld for WizardAssets {
 #[auto_name]
 #[derive(Component, Debug, Default, Copy, Clone, Reflect)]
 #[reflect(Component)]
+#[require(DefenseTarget = DefenseTarget::new(1.5))]
 pub struct Wizard;
 
 #[auto_plugin(app=app)]
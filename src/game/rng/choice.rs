@@ -0,0 +1,161 @@
+//! Picking items from a set, e.g. which enemy a spawner sends next.
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+/// Items picked with a probability proportional to their weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedTable<T> {
+    items: Vec<T>,
+    /// Running sum of the weights, the last entry is the total.
+    cumulative: Vec<f32>,
+}
+
+impl<T> WeightedTable<T> {
+    /// Entries without a positive, finite weight are left out.
+    /// Returns `None` if there is nothing left to pick from.
+    pub fn new(entries: impl IntoIterator<Item = (T, f32)>) -> Option<Self> {
+        let mut items = Vec::new();
+        let mut cumulative = Vec::new();
+        let mut total = 0.0;
+        for (item, weight) in entries {
+            if !weight.is_finite() || weight <= 0.0 {
+                continue;
+            }
+            total += weight;
+            items.push(item);
+            cumulative.push(total);
+        }
+        if items.is_empty() {
+            return None;
+        }
+        Some(Self { items, cumulative })
+    }
+
+    pub fn total_weight(&self) -> f32 {
+        *self.cumulative.last().expect("table is never empty")
+    }
+
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        let roll = rng.random_range(0.0..self.total_weight());
+        let ix = self.cumulative.partition_point(|&sum| sum <= roll);
+        &self.items[ix.min(self.items.len() - 1)]
+    }
+}
+
+/// Hands out every item once, in random order, before starting over with a new order.
+/// Avoids the long streaks and droughts of independent rolls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShuffleBag<T> {
+    items: Vec<T>,
+    drawn: usize,
+}
+
+impl<T> ShuffleBag<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        Self { items, drawn: 0 }
+    }
+
+    /// Returns `None` only if the bag is empty.
+    pub fn draw<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<&T> {
+        if self.items.is_empty() {
+            return None;
+        }
+        if self.drawn == 0 {
+            self.items.shuffle(rng);
+        }
+        let ix = self.drawn;
+        self.drawn = (self.drawn + 1) % self.items.len();
+        Some(&self.items[ix])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::{Prng, ZERO_SEED};
+    use rand::SeedableRng;
+
+    const SAMPLES: usize = 20_000;
+
+    fn rng() -> Prng {
+        Prng::from_seed(ZERO_SEED)
+    }
+
+    #[test]
+    fn weighted_table_rejects_empty() {
+        assert!(WeightedTable::<u8>::new([]).is_none());
+        assert!(WeightedTable::new([('a', 0.0), ('b', -1.0), ('c', f32::NAN)]).is_none());
+    }
+
+    #[test]
+    fn weighted_table_follows_weights() {
+        let table = WeightedTable::new([('a', 1.0), ('b', 0.0), ('c', 3.0), ('d', 6.0)]).unwrap();
+        assert_eq!(table.total_weight(), 10.0);
+        let mut rng = rng();
+        let mut counts = [0usize; 4];
+        for _ in 0..SAMPLES {
+            counts[(*table.choose(&mut rng) as u8 - b'a') as usize] += 1;
+        }
+        assert_eq!(counts[1], 0, "zero weight was picked");
+        for (ix, expected) in [(0, 0.1), (2, 0.3), (3, 0.6)] {
+            let actual = counts[ix] as f32 / SAMPLES as f32;
+            assert!(
+                (actual - expected).abs() < 0.02,
+                "entry {ix}: expected {expected}, got {actual}"
+            );
+        }
+    }
+
+    #[test]
+    fn weighted_table_single_entry() {
+        let table = WeightedTable::new([(7, 0.5)]).unwrap();
+        let mut rng = rng();
+        assert!((0..100).all(|_| *table.choose(&mut rng) == 7));
+    }
+
+    #[test]
+    fn shuffle_bag_draws_every_item_once_per_round() {
+        let mut bag = ShuffleBag::new((0..10).collect());
+        let mut rng = rng();
+        for _ in 0..20 {
+            let mut round: Vec<i32> = (0..10).map(|_| *bag.draw(&mut rng).unwrap()).collect();
+            round.sort();
+            assert_eq!(round, (0..10).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn shuffle_bag_order_changes_between_rounds() {
+        let mut bag = ShuffleBag::new((0..10).collect());
+        let mut rng = rng();
+        let rounds: Vec<Vec<i32>> = (0..5)
+            .map(|_| (0..10).map(|_| *bag.draw(&mut rng).unwrap()).collect())
+            .collect();
+        assert!(rounds.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn shuffle_bag_positions_are_uniform() {
+        let mut bag = ShuffleBag::new(vec![0usize, 1, 2, 3]);
+        let mut rng = rng();
+        let rounds = SAMPLES / 4;
+        let mut first = [0usize; 4];
+        for _ in 0..rounds {
+            first[*bag.draw(&mut rng).unwrap()] += 1;
+            for _ in 0..3 {
+                bag.draw(&mut rng);
+            }
+        }
+        for count in first {
+            let actual = count as f32 / rounds as f32;
+            assert!((actual - 0.25).abs() < 0.03, "first draw share {actual}");
+        }
+    }
+
+    #[test]
+    fn empty_shuffle_bag() {
+        let mut bag = ShuffleBag::<u8>::new(Vec::new());
+        assert_eq!(bag.draw(&mut rng()), None);
+    }
+}
@@ -39,6 +39,7 @@ pub(crate) fn plugin(_app: &mut App) {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game::rng::stream::{CosmeticRng, GameplayRng, fork_rng_streams};
     use crate::game::rng::{RngPlugin, SEED_LEN, ZERO_SEED};
     use bevy::ecs::system::RunSystemOnce;
     use rand::RngCore;
@@ -96,4 +97,24 @@ mod tests {
         set_seed(&mut app, [1; SEED_LEN]);
         assert_eq!(get_seed(&mut app), [1; SEED_LEN]);
     }
+
+    #[test]
+    fn cosmetic_draws_leave_gameplay_alone() {
+        fn gameplay_rolls(cosmetic_entities: usize) -> Vec<u32> {
+            let mut app = test_app();
+            set_seed(&mut app, ZERO_SEED);
+            app.world_mut().run_system_once(fork_rng_streams).unwrap();
+            let world = app.world_mut();
+            // Every cosmetic entity forks the cosmetic root and rolls on its own stream.
+            for _ in 0..cosmetic_entities {
+                let mut entity = world.spawn(CosmeticRng::default());
+                entity.get_mut::<CosmeticRng>().unwrap().next_u32();
+            }
+            let mut entity = world.spawn(GameplayRng::default());
+            let mut gameplay = entity.get_mut::<GameplayRng>().unwrap();
+            (0..4).map(|_| gameplay.next_u32()).collect()
+        }
+
+        assert_eq!(gameplay_rolls(0), gameplay_rolls(100));
+    }
 }
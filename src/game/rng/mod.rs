@@ -1,5 +1,8 @@
+pub mod choice;
 pub mod global;
+pub mod seed;
 pub mod sphere;
+pub mod stream;
 
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
@@ -19,5 +22,7 @@ impl Plugin for RngPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(EntropyPlugin::<Prng>::with_seed(ZERO_SEED));
         app.add_plugins(global::plugin);
+        app.add_plugins(seed::plugin);
+        app.add_plugins(stream::plugin);
     }
 }
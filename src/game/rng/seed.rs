@@ -0,0 +1,101 @@
+//! Choosing the seed a run is played with.
+//!
+//! Seeds are shown and entered as 16 hex digits so they can be shared.
+
+use crate::game::rng::global::GlobalRng;
+use crate::game::rng::{Prng, SEED_LEN, Seed};
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use rand::{Rng, SeedableRng};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Seed the next run will be started with.
+#[auto_register_type]
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct SelectedSeed(pub Seed);
+
+impl FromWorld for SelectedSeed {
+    fn from_world(_world: &mut World) -> Self {
+        Self(random_seed())
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<SelectedSeed>();
+}
+
+pub fn format_seed(seed: Seed) -> String {
+    format!(
+        "{:0width$X}",
+        u64::from_le_bytes(seed),
+        width = SEED_LEN * 2
+    )
+}
+
+/// Parses up to 16 hex digits, case insensitive.
+pub fn parse_seed(text: &str) -> Option<Seed> {
+    let text = text.trim();
+    // `from_str_radix` would also take a leading sign.
+    if text.is_empty()
+        || text.len() > SEED_LEN * 2
+        || !text.bytes().all(|byte| byte.is_ascii_hexdigit())
+    {
+        return None;
+    }
+    u64::from_str_radix(text, 16).ok().map(u64::to_le_bytes)
+}
+
+/// A seed that is new every launch.
+pub fn random_seed() -> Seed {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    Prng::seed_from_u64(nanos).random::<u64>().to_le_bytes()
+}
+
+/// The same seed for everyone on a given (UTC) day.
+pub fn daily_seed() -> Seed {
+    let day = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECS_PER_DAY;
+    Prng::seed_from_u64(day).random::<u64>().to_le_bytes()
+}
+
+/// Reseeds the global rng with the [`SelectedSeed`] so the run can be reproduced.
+pub fn apply_selected_seed(selected: Res<SelectedSeed>, mut rng: GlobalRng) {
+    rng.reseed(selected.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_text_round_trip() {
+        for seed in [
+            [0; SEED_LEN],
+            [0xAB; SEED_LEN],
+            0x0123_4567_89AB_CDEF_u64.to_le_bytes(),
+        ] {
+            let text = format_seed(seed);
+            assert_eq!(text.len(), SEED_LEN * 2);
+            assert_eq!(parse_seed(&text), Some(seed));
+        }
+        assert_eq!(format_seed(1u64.to_le_bytes()), "0000000000000001");
+        assert_eq!(parse_seed(" beef "), Some(0xBEEF_u64.to_le_bytes()));
+    }
+
+    #[test]
+    fn rejects_invalid_seed_text() {
+        for text in ["", "   ", "0123456789ABCDEF0", "XYZ", "12 34", "+1", "-1"] {
+            assert_eq!(parse_seed(text), None, "{text:?}");
+        }
+    }
+}
@@ -1,12 +1,10 @@
-use crate::game::rng::Prng;
 use bevy::prelude::*;
-use bevy_rand::prelude::GlobalEntropy;
 use rand::Rng;
-use std::f32::consts::PI;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
 /// Samples a uniformly random point on the unit sphere (radius = 1.0),
 /// returning [`Vec3`].
-pub fn sample_point_on_sphere<R: Rng>(rng: &mut R) -> Vec3 {
+pub fn sample_point_on_sphere<R: Rng + ?Sized>(rng: &mut R) -> Vec3 {
     // Pick z ∈ [-1, 1] uniformly, and θ ∈ [0, 2π) uniformly.
     let z: f32 = rng.random_range(-1.0..1.0);
     let theta: f32 = rng.random_range(0.0..(2.0 * PI));
@@ -20,12 +18,185 @@ pub fn sample_point_on_sphere<R: Rng>(rng: &mut R) -> Vec3 {
     Vec3::new(x, y, z)
 }
 
+/// Samples a uniformly random point inside the unit ball.
+pub fn sample_point_in_ball<R: Rng + ?Sized>(rng: &mut R) -> Vec3 {
+    // Volume grows with r³, so the cube root keeps the density even.
+    let r = rng.random_range(0.0f32..=1.0).cbrt();
+    sample_point_on_sphere(rng) * r
+}
+
+/// Samples a uniformly random point inside the unit disc.
+pub fn sample_point_in_disc<R: Rng + ?Sized>(rng: &mut R) -> Vec2 {
+    sample_point_in_annulus(rng, 0.0, 1.0)
+}
+
+/// Samples a uniformly random point between the `inner` and `outer` radius.
+pub fn sample_point_in_annulus<R: Rng + ?Sized>(rng: &mut R, inner: f32, outer: f32) -> Vec2 {
+    let (inner, outer) = (inner.min(outer).max(0.0), inner.max(outer).max(0.0));
+    // Area grows with r², so pick r² uniformly.
+    let r = rng.random_range(inner * inner..=outer * outer).sqrt();
+    let theta = rng.random_range(0.0..TAU);
+    Vec2::from_angle(theta) * r
+}
+
+/// Samples a uniformly random unit direction at most `half_angle` radians away from `axis`.
+pub fn sample_direction_in_cone<R: Rng + ?Sized>(rng: &mut R, axis: Vec3, half_angle: f32) -> Vec3 {
+    let axis = axis.normalize_or(Vec3::Y);
+    let (perp1, perp2) = axis.any_orthonormal_pair();
+    // The area of a spherical cap grows linearly with 1 - cos θ.
+    let phi = rng.random_range(0.0f32..TAU);
+    let cos_theta = rng.random_range(half_angle.clamp(0.0, PI).cos()..=1.0);
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    perp1 * (sin_theta * phi.cos()) + perp2 * (sin_theta * phi.sin()) + axis * cos_theta
+}
+
+/// Samples a uniformly random unit direction on the hemisphere whose pole is `pole`.
+pub fn sample_direction_in_hemisphere<R: Rng + ?Sized>(rng: &mut R, pole: Vec3) -> Vec3 {
+    sample_direction_in_cone(rng, pole, FRAC_PI_2)
+}
+
 pub trait RandomSpherePoint {
     fn random_sphere_point(&mut self, radius: f32) -> Vec3;
 }
 
-impl RandomSpherePoint for GlobalEntropy<'_, Prng> {
+impl<R: Rng + ?Sized> RandomSpherePoint for R {
     fn random_sphere_point(&mut self, radius: f32) -> Vec3 {
         sample_point_on_sphere(self) * radius
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::{Prng, ZERO_SEED};
+    use rand::SeedableRng;
+
+    const SAMPLES: usize = 20_000;
+    /// Allowed deviation of an observed fraction from the expected one.
+    const TOLERANCE: f32 = 0.02;
+
+    fn rng() -> Prng {
+        Prng::from_seed(ZERO_SEED)
+    }
+
+    fn fraction<T>(samples: &[T], predicate: impl Fn(&T) -> bool) -> f32 {
+        samples.iter().filter(|s| predicate(s)).count() as f32 / samples.len() as f32
+    }
+
+    fn assert_near(actual: f32, expected: f32, what: &str) {
+        assert!(
+            (actual - expected).abs() < TOLERANCE,
+            "{what}: expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn sphere_points_are_on_the_surface_and_uniform() {
+        let mut rng = rng();
+        let points: Vec<_> = (0..SAMPLES)
+            .map(|_| sample_point_on_sphere(&mut rng))
+            .collect();
+        assert!(points.iter().all(|p| (p.length() - 1.0).abs() < 1e-4));
+        // Archimedes: the cap above z = 0.5 covers a quarter of the sphere.
+        assert_near(fraction(&points, |p| p.z > 0.5), 0.25, "cap z > 0.5");
+        assert_near(fraction(&points, |p| p.x > 0.0), 0.5, "half x > 0");
+        assert_near(fraction(&points, |p| p.y > 0.0), 0.5, "half y > 0");
+    }
+
+    #[test]
+    fn ball_points_are_inside_and_uniform() {
+        let mut rng = rng();
+        let points: Vec<_> = (0..SAMPLES)
+            .map(|_| sample_point_in_ball(&mut rng))
+            .collect();
+        assert!(points.iter().all(|p| p.length() <= 1.0 + 1e-4));
+        assert_near(fraction(&points, |p| p.length() < 0.5), 0.125, "inner ball");
+        assert_near(
+            fraction(&points, |p| p.x > 0.0 && p.y > 0.0 && p.z > 0.0),
+            0.125,
+            "octant",
+        );
+    }
+
+    #[test]
+    fn disc_points_are_inside_and_uniform() {
+        let mut rng = rng();
+        let points: Vec<_> = (0..SAMPLES)
+            .map(|_| sample_point_in_disc(&mut rng))
+            .collect();
+        assert!(points.iter().all(|p| p.length() <= 1.0 + 1e-4));
+        assert_near(fraction(&points, |p| p.length() < 0.5), 0.25, "inner disc");
+        assert_near(
+            fraction(&points, |p| p.x > 0.0 && p.y > 0.0),
+            0.25,
+            "quadrant",
+        );
+    }
+
+    #[test]
+    fn annulus_points_are_between_radii_and_uniform() {
+        let mut rng = rng();
+        let (inner, outer) = (2.0, 4.0);
+        let points: Vec<_> = (0..SAMPLES)
+            .map(|_| sample_point_in_annulus(&mut rng, inner, outer))
+            .collect();
+        assert!(
+            points
+                .iter()
+                .all(|p| p.length() >= inner - 1e-4 && p.length() <= outer + 1e-4)
+        );
+        // Radius splitting the annulus into two rings of equal area.
+        let median = ((inner * inner + outer * outer) / 2.0).sqrt();
+        assert_near(
+            fraction(&points, |p| p.length() < median),
+            0.5,
+            "inner ring",
+        );
+    }
+
+    #[test]
+    fn annulus_accepts_swapped_radii() {
+        let mut rng = rng();
+        for _ in 0..100 {
+            let length = sample_point_in_annulus(&mut rng, 3.0, 1.0).length();
+            assert!((1.0 - 1e-4..=3.0 + 1e-4).contains(&length));
+        }
+    }
+
+    #[test]
+    fn hemisphere_directions_face_the_pole() {
+        let mut rng = rng();
+        let pole = Vec3::new(1.0, 2.0, -1.0).normalize();
+        let dots: Vec<_> = (0..SAMPLES)
+            .map(|_| sample_direction_in_hemisphere(&mut rng, pole))
+            .inspect(|d| assert!((d.length() - 1.0).abs() < 1e-4))
+            .map(|d| d.dot(pole))
+            .collect();
+        assert!(dots.iter().all(|&dot| dot >= -1e-4));
+        // Uniform over the area means the height above the equator is uniform too.
+        let mean = dots.iter().sum::<f32>() / dots.len() as f32;
+        assert_near(mean, 0.5, "mean height");
+        assert_near(fraction(&dots, |&dot| dot > 0.5), 0.5, "upper half");
+    }
+
+    #[test]
+    fn cone_directions_stay_within_angle() {
+        let mut rng = rng();
+        let axis = Vec3::NEG_Z;
+        let half_angle = 20f32.to_radians();
+        let angles: Vec<_> = (0..SAMPLES)
+            .map(|_| sample_direction_in_cone(&mut rng, axis, half_angle).angle_between(axis))
+            .collect();
+        assert!(angles.iter().all(|&angle| angle <= half_angle + 1e-3));
+        // Half the cap's area lies within this angle.
+        let median = ((1.0 + half_angle.cos()) / 2.0).acos();
+        assert_near(fraction(&angles, |&angle| angle < median), 0.5, "inner cap");
+    }
+
+    #[test]
+    fn zero_angle_cone_is_the_axis() {
+        let mut rng = rng();
+        let direction = sample_direction_in_cone(&mut rng, Vec3::X * 5.0, 0.0);
+        assert!(direction.abs_diff_eq(Vec3::X, 1e-4));
+    }
+}
@@ -0,0 +1,101 @@
+//! Independent random streams forked from the global rng.
+//!
+//! Gameplay and cosmetic randomness each get their own root stream and entities fork a stream of
+//! their own from those when the component is added. Extra rolls made for visuals can't shift
+//! gameplay rolls, so runs stay reproducible from their seed when effects change.
+
+use crate::game::rng::global::GlobalRng;
+use crate::game::rng::{Prng, ZERO_SEED};
+use bevy::ecs::component::HookContext;
+use bevy::ecs::world::DeferredWorld;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use bevy_rand::prelude::{Entropy, ForkableRng};
+use rand::SeedableRng;
+
+/// Root streams the per-entity streams are forked from.
+#[auto_register_type]
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct RngStreams {
+    gameplay: Entropy<Prng>,
+    cosmetic: Entropy<Prng>,
+}
+
+impl Default for RngStreams {
+    fn default() -> Self {
+        let mut root = Entropy::<Prng>::from_seed(ZERO_SEED);
+        Self {
+            gameplay: root.fork_rng(),
+            cosmetic: root.fork_rng(),
+        }
+    }
+}
+
+/// Randomness that affects the outcome of a run.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect, Deref, DerefMut)]
+#[reflect(Component)]
+#[component(on_add = Self::on_add)]
+pub struct GameplayRng(Entropy<Prng>);
+
+impl Default for GameplayRng {
+    fn default() -> Self {
+        Self(Entropy::from_seed(ZERO_SEED))
+    }
+}
+
+impl GameplayRng {
+    fn on_add(mut world: DeferredWorld, ctx: HookContext) {
+        let Some(forked) = fork(&mut world, |streams| &mut streams.gameplay) else {
+            return;
+        };
+        if let Some(mut rng) = world.get_mut::<Self>(ctx.entity) {
+            rng.0 = forked;
+        }
+    }
+}
+
+/// Randomness that is only used for visuals and audio.
+#[auto_register_type]
+#[derive(Component, Debug, Clone, Reflect, Deref, DerefMut)]
+#[reflect(Component)]
+#[component(on_add = Self::on_add)]
+pub struct CosmeticRng(Entropy<Prng>);
+
+impl Default for CosmeticRng {
+    fn default() -> Self {
+        Self(Entropy::from_seed(ZERO_SEED))
+    }
+}
+
+impl CosmeticRng {
+    fn on_add(mut world: DeferredWorld, ctx: HookContext) {
+        let Some(forked) = fork(&mut world, |streams| &mut streams.cosmetic) else {
+            return;
+        };
+        if let Some(mut rng) = world.get_mut::<Self>(ctx.entity) {
+            rng.0 = forked;
+        }
+    }
+}
+
+fn fork(
+    world: &mut DeferredWorld,
+    stream: fn(&mut RngStreams) -> &mut Entropy<Prng>,
+) -> Option<Entropy<Prng>> {
+    let mut streams = world.get_resource_mut::<RngStreams>()?;
+    Some(stream(&mut streams).fork_rng())
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<RngStreams>();
+}
+
+/// Forks new root streams from the global rng, run after it was reseeded.
+pub fn fork_rng_streams(mut rng: GlobalRng, mut streams: ResMut<RngStreams>) {
+    let root = rng.rng();
+    streams.gameplay = root.fork_rng();
+    streams.cosmetic = root.fork_rng();
+}
@@ -1,15 +1,17 @@
-use std::f32::consts::PI;
-use std::time::Duration;
-
-use crate::game::camera::CameraTarget;
+use crate::game::behaviors::target_select::DefenseTarget;
+use crate::game::camera::target::CameraTarget;
 use crate::game::effects::lightning_ball::{LightningBall, LightningBallConduit};
-use crate::game::prefabs::enemy::Enemy;
+use crate::game::prefabs::obstacle::Obstacle;
 use crate::game::prefabs::spawner::Spawner;
 use crate::game::prefabs::tower::Tower;
 use crate::game::prefabs::wizard::Wizard;
+use crate::game::rng::global::GlobalRng;
+use crate::game::scenes::level::{CurrentLevel, Levels};
 use crate::game::screens::Screen;
-use avian3d::prelude::{Collider, RigidBody};
-use bevy::color::palettes::css::GREEN;
+use crate::game::terrain::{
+    KeepClear, TerrainConfig, generate_heightmap, level_seed, paths, props, terrain,
+};
+use avian3d::prelude::Collider;
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
@@ -21,46 +23,70 @@ pub struct LevelRoot;
 #[auto_plugin(app=_app)]
 pub fn plugin(_app: &mut App) {}
 
-pub fn spawn_level(
+pub fn spawn_current_level(
     mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    levels: Levels,
+    rng: GlobalRng,
+    terrain_config: Res<TerrainConfig>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
+    let Some(level) = levels.get(current_level.0) else {
+        error!("no level at index {}", current_level.0);
+        return;
+    };
+
+    let seed = level_seed(rng.seed_bytes(), current_level.0);
+    let spawner_points = level
+        .spawners
+        .iter()
+        .map(|spawner| spawner.translation.xz())
+        .collect::<Vec<_>>();
+    let tower_points = level
+        .towers
+        .iter()
+        .map(|tower| tower.xz())
+        .collect::<Vec<_>>();
+    let heightmap = generate_heightmap(
+        seed,
+        level.ground_size.xz(),
+        &tower_points,
+        &spawner_points,
+        &terrain_config,
+    );
+    // Level heights of ground objects are relative to the surface of the original flat ground.
+    let on_terrain = |translation: Vec3| {
+        translation + Vec3::Y * (heightmap.height_at(translation.xz()) - level.ground_surface())
+    };
+
     let level_ent = commands
         .spawn((
-            Name::new("Level"),
+            Name::new(format!("Level: {}", level.name)),
             LevelRoot,
             StateScoped(Screen::Gameplay),
             Transform::default(),
             Visibility::default(),
             children![
-                (
-                    Name::new("Grass"),
-                    Mesh3d(meshes.add(Cuboid::new(1000.0, 10.0, 1000.0))),
-                    Collider::cuboid(1000.0, 10.0, 1000.0),
-                    RigidBody::Static,
-                    MeshMaterial3d(materials.add(StandardMaterial {
-                        base_color: Color::from(GREEN),
-                        perceptual_roughness: 1.0,
-                        reflectance: 0.0,
-                        ..Default::default()
-                    })),
-                ),
+                terrain(&heightmap, &mut meshes, &mut materials),
                 (
                     LightningBall,
-                    CameraTarget,
-                    Transform::from_xyz(0.0, 3.1 * 10.0 + 100.0, 0.8 * 10.0),
+                    CameraTarget::default(),
+                    Transform::from_translation(level.lightning_ball),
                 ),
             ],
         ))
         .id();
 
-    let _tower_ent = commands
-        .entity(level_ent)
-        .with_child((
+    for (index, &translation) in level.towers.iter().enumerate() {
+        let mut tower = commands.spawn((
             Tower,
-            Transform::from_xyz(0.0, 50.0, 0.0),
-            children![(
+            Transform::from_translation(translation),
+            ChildOf(level_ent),
+        ));
+        // The wizard stands on the first tower.
+        if index == 0 {
+            tower.with_child((
                 Wizard,
                 Transform::from_xyz(0.0, 50.0, 0.0).with_scale(Vec3::splat(10.0)),
                 children![(
@@ -69,41 +95,59 @@ pub fn spawn_level(
                     Transform::from_xyz(-0.81, 1.95, -0.09),
                     Collider::sphere(0.25)
                 )],
-            ),],
-        ))
-        .id();
+            ));
+        }
+    }
 
-    // Spawners
-    for (x, y) in equidistant_points_on_circle(300., 5) {
+    for spawner in level.spawners.iter() {
         commands.entity(level_ent).with_child((
-            Spawner {
-                spawns: Enemy::BaseSkele,
-                spawn_duration: Duration::from_secs_f32(4.0),
-                time_to_next_spawn: Duration::from_secs_f32(0.0),
-                spawn_left: 4,
-            },
-            Transform::from_xyz(x, 10.0, y),
+            Spawner::new(
+                spawner.spawns,
+                spawner.mix.clone(),
+                spawner.spawn_duration(),
+                spawner.initial_delay(),
+                spawner.spawn_count,
+            ),
+            Transform::from_translation(on_terrain(spawner.translation)),
         ));
     }
-}
 
-/// Generate points along a circle.
-// TODO move to a more appropriate place
-pub fn equidistant_points_on_circle(radius: f32, num_points: usize) -> Vec<(f32, f32)> {
-    if radius < 0.0 {
-        panic!("Radius cannot be negative.");
+    for conduit in level.conduits.iter() {
+        commands.entity(level_ent).with_child((
+            Name::new("Conduit"),
+            LightningBallConduit,
+            DefenseTarget::new(0.5),
+            Transform::from_translation(conduit.translation),
+            Collider::sphere(conduit.radius),
+        ));
+    }
+
+    for obstacle in level.obstacles.iter() {
+        commands.entity(level_ent).with_child((
+            Obstacle {
+                size: obstacle.size,
+            },
+            Transform::from_translation(on_terrain(obstacle.translation))
+                .with_rotation(Quat::from_rotation_y(obstacle.yaw_deg.to_radians())),
+        ));
     }
 
-    if num_points == 0 {
-        return Vec::new();
+    let path_clearance = terrain_config.path_radius + terrain_config.path_falloff;
+    let keep_clear = paths(&spawner_points, &tower_points)
+        .map(|(spawner, tower)| KeepClear::Segment(spawner, tower, path_clearance))
+        .chain(tower_points.iter().map(|&tower| {
+            KeepClear::Circle(
+                tower,
+                terrain_config.tower_clearing_radius + terrain_config.path_falloff,
+            )
+        }))
+        .chain(level.obstacles.iter().map(|obstacle| {
+            KeepClear::Circle(obstacle.translation.xz(), obstacle.size.xz().length())
+        }))
+        .collect::<Vec<_>>();
+    for (prop, transform) in props(seed, &heightmap, &keep_clear, &terrain_config) {
+        commands.entity(level_ent).with_child((prop, transform));
     }
 
-    (0..num_points)
-        .map(|i| {
-            let angle = (i as f32) * (2.0 * PI) / (num_points as f32);
-            let x = radius * angle.cos();
-            let y = radius * angle.sin();
-            (x, y)
-        })
-        .collect()
+    commands.insert_resource(heightmap);
 }
@@ -0,0 +1,266 @@
+//! Data driven level layouts loaded from `*.level.ron` files.
+
+use crate::game::asset_tracking::LoadResource;
+use crate::game::prefabs::enemy::Enemy;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Layout of a level, built by [`spawn_current_level`](super::game::spawn_current_level).
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelDefinition {
+    pub name: String,
+    /// X and Z are the extents of the terrain. Y is the thickness of the flat ground levels
+    /// were originally laid out on, heights of spawners and obstacles are relative to its top.
+    pub ground_size: Vec3,
+    pub lightning_ball: Vec3,
+    /// Enemies win once all of these have fallen.
+    pub towers: Vec<Vec3>,
+    #[serde(default)]
+    pub spawners: Vec<SpawnerDefinition>,
+    #[serde(default)]
+    pub conduits: Vec<ConduitDefinition>,
+    #[serde(default)]
+    pub obstacles: Vec<ObstacleDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpawnerDefinition {
+    pub translation: Vec3,
+    pub spawns: Enemy,
+    /// Weighted enemies rolled for each spawn instead of `spawns`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mix: Vec<(Enemy, f32)>,
+    pub spawn_duration_secs: f32,
+    #[serde(default)]
+    pub initial_delay_secs: f32,
+    pub spawn_count: u32,
+}
+
+impl SpawnerDefinition {
+    pub fn spawn_duration(&self) -> Duration {
+        Duration::from_secs_f32(self.spawn_duration_secs.max(0.0))
+    }
+
+    pub fn initial_delay(&self) -> Duration {
+        Duration::from_secs_f32(self.initial_delay_secs.max(0.0))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConduitDefinition {
+    pub translation: Vec3,
+    pub radius: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObstacleDefinition {
+    pub translation: Vec3,
+    #[serde(default)]
+    pub yaw_deg: f32,
+    pub size: Vec3,
+}
+
+impl LevelDefinition {
+    /// Height of the flat ground surface the level was laid out on.
+    pub fn ground_surface(&self) -> f32 {
+        self.ground_size.y / 2.0
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+}
+
+/// Ordered list of the playable levels, loaded from a `*.levels.ron` file listing level paths.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct LevelRegistry {
+    #[dependency]
+    pub levels: Vec<Handle<LevelDefinition>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LevelRegistryFile {
+    levels: Vec<String>,
+}
+
+#[auto_register_type]
+#[derive(Resource, Asset, Debug, Clone, Reflect)]
+pub struct LevelAssets {
+    #[dependency]
+    pub registry: Handle<LevelRegistry>,
+}
+
+impl FromWorld for LevelAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            registry: assets.load("levels/all.levels.ron"),
+        }
+    }
+}
+
+/// Index into the [`LevelRegistry`] of the level to build when entering gameplay.
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct CurrentLevel(pub usize);
+
+/// Access to the registered levels, empty until [`LevelAssets`] finished loading.
+#[derive(SystemParam)]
+pub struct Levels<'w> {
+    level_assets: Option<Res<'w, LevelAssets>>,
+    registries: Res<'w, Assets<LevelRegistry>>,
+    definitions: Res<'w, Assets<LevelDefinition>>,
+}
+
+impl Levels<'_> {
+    fn registry(&self) -> Option<&LevelRegistry> {
+        self.registries.get(&self.level_assets.as_ref()?.registry)
+    }
+
+    pub fn count(&self) -> usize {
+        self.registry().map_or(0, |registry| registry.levels.len())
+    }
+
+    pub fn handle(&self, index: usize) -> Option<&Handle<LevelDefinition>> {
+        self.registry()?.levels.get(index)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&LevelDefinition> {
+        self.definitions.get(self.handle(index)?)
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelDefinitionLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for LevelDefinitionLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read level: {err}"),
+            Self::Ron(err) => write!(f, "could not parse level: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for LevelDefinitionLoaderError {}
+
+impl From<std::io::Error> for LevelDefinitionLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for LevelDefinitionLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Ron(err)
+    }
+}
+
+#[derive(Default)]
+struct LevelDefinitionLoader;
+
+impl AssetLoader for LevelDefinitionLoader {
+    type Asset = LevelDefinition;
+    type Settings = ();
+    type Error = LevelDefinitionLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+#[derive(Default)]
+struct LevelRegistryLoader;
+
+impl AssetLoader for LevelRegistryLoader {
+    type Asset = LevelRegistry;
+    type Settings = ();
+    type Error = LevelDefinitionLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: LevelRegistryFile = ron::de::from_bytes(&bytes)?;
+        Ok(LevelRegistry {
+            levels: file
+                .levels
+                .into_iter()
+                .map(|path| load_context.load(path))
+                .collect(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["levels.ron"]
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.init_asset::<LevelDefinition>();
+    app.init_asset_loader::<LevelDefinitionLoader>();
+    app.init_asset::<LevelRegistry>();
+    app.init_asset_loader::<LevelRegistryLoader>();
+    app.load_resource::<LevelAssets>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ron_round_trip() {
+        let level = LevelDefinition {
+            name: "Round Trip".to_string(),
+            ground_size: Vec3::new(1000.0, 10.0, 1000.0),
+            lightning_ball: Vec3::new(0.0, 131.0, 8.0),
+            towers: vec![Vec3::new(0.0, 50.0, 0.0), Vec3::new(200.0, 50.0, -150.0)],
+            spawners: vec![SpawnerDefinition {
+                translation: Vec3::new(350.0, 10.0, 0.0),
+                spawns: Enemy::SkeleArcher,
+                mix: vec![(Enemy::BaseSkele, 3.0), (Enemy::SkeleArcher, 1.0)],
+                spawn_duration_secs: 3.0,
+                initial_delay_secs: 0.5,
+                spawn_count: 6,
+            }],
+            conduits: vec![ConduitDefinition {
+                translation: Vec3::new(100.0, 30.0, -50.0),
+                radius: 5.0,
+            }],
+            obstacles: vec![ObstacleDefinition {
+                translation: Vec3::new(-120.0, 15.0, 80.0),
+                yaw_deg: 45.0,
+                size: Vec3::new(40.0, 20.0, 40.0),
+            }],
+        };
+        let ron = level.to_ron().unwrap();
+        assert_eq!(ron::de::from_str::<LevelDefinition>(&ron).unwrap(), level);
+    }
+}
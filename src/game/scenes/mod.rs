@@ -2,8 +2,12 @@ use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
 pub mod game;
+pub mod level;
+pub mod progress;
 
 #[auto_plugin(app=app)]
 pub(crate) fn plugin(app: &mut App) {
     app.add_plugins(game::plugin);
+    app.add_plugins(level::plugin);
+    app.add_plugins(progress::plugin);
 }
@@ -0,0 +1,213 @@
+//! Level unlock progression, persisted between runs, and how a level ends.
+
+use crate::game::health::Dead;
+use crate::game::menus::Menu;
+use crate::game::pause_controller::{PausableSystems, Pause};
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::prefabs::enemy_pool::InPlay;
+use crate::game::prefabs::spawner::Spawner;
+use crate::game::prefabs::tower::Tower;
+use crate::game::scenes::game::LevelRoot;
+use crate::game::scenes::level::CurrentLevel;
+use crate::game::screens::Screen;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+
+/// Saved in the platform data directory natively and in local storage on the web.
+const SAVE_FILE: &str = "progress.ron";
+
+#[auto_register_type]
+#[derive(Resource, Debug, SmartDefault, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct LevelProgress {
+    /// Number of levels that can be played, counted from the first one.
+    #[default(1)]
+    pub unlocked: usize,
+}
+
+impl LevelProgress {
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        index < self.unlocked
+    }
+
+    fn load() -> Self {
+        let Some(ron) = storage::read() else {
+            return Self::default();
+        };
+        ron::de::from_str(&ron).unwrap_or_else(|err| {
+            warn!("ignoring invalid saved progress: {err}");
+            Self::default()
+        })
+    }
+
+    fn save(&self) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|ron| storage::write(&ron));
+        if let Err(err) = result {
+            error!("failed to save progress: {err}");
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod storage {
+    use super::SAVE_FILE;
+    use std::path::PathBuf;
+
+    /// Per-user data directory of the platform, without pulling in a crate for it.
+    fn data_dir() -> Option<PathBuf> {
+        let env_dir = |var: &str| {
+            std::env::var_os(var)
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+        };
+        if cfg!(target_os = "windows") {
+            env_dir("APPDATA")
+        } else if cfg!(target_os = "macos") {
+            env_dir("HOME").map(|home| home.join("Library/Application Support"))
+        } else {
+            env_dir("XDG_DATA_HOME")
+                .or_else(|| env_dir("HOME").map(|home| home.join(".local/share")))
+        }
+    }
+
+    /// Falls back to the working directory when the data directory is unknown.
+    fn save_path() -> PathBuf {
+        data_dir()
+            .map_or_else(
+                || PathBuf::from("save"),
+                |dir| dir.join(env!("CARGO_PKG_NAME")),
+            )
+            .join(SAVE_FILE)
+    }
+
+    pub(super) fn read() -> Option<String> {
+        std::fs::read_to_string(save_path()).ok()
+    }
+
+    pub(super) fn write(ron: &str) -> Result<(), String> {
+        let path = save_path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+        }
+        std::fs::write(&path, ron).map_err(|err| format!("{}: {err}", path.display()))
+    }
+}
+
+#[cfg(target_family = "wasm")]
+mod storage {
+    use super::SAVE_FILE;
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok().flatten()
+    }
+
+    fn key() -> String {
+        format!("{}/{SAVE_FILE}", env!("CARGO_PKG_NAME"))
+    }
+
+    pub(super) fn read() -> Option<String> {
+        local_storage()?.get_item(&key()).ok().flatten()
+    }
+
+    pub(super) fn write(ron: &str) -> Result<(), String> {
+        local_storage()
+            .ok_or("local storage is unavailable")?
+            .set_item(&key(), ron)
+            .map_err(|err| format!("{err:?}"))
+    }
+}
+
+/// Added to the [`LevelRoot`] once every spawner is exhausted and all enemies are dead.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct LevelComplete;
+
+/// Added to the [`LevelRoot`] once every tower has fallen.
+#[auto_register_type]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct LevelFailed;
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.insert_resource(LevelProgress::load());
+    app.add_systems(
+        Update,
+        (detect_level_complete, detect_level_failed)
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+    app.add_observer(on_level_complete);
+    app.add_observer(on_level_failed);
+}
+
+fn detect_level_complete(
+    mut commands: Commands,
+    level_root: Single<
+        Entity,
+        (
+            With<LevelRoot>,
+            Without<LevelComplete>,
+            Without<LevelFailed>,
+        ),
+    >,
+    spawners: Query<&Spawner>,
+    alive_enemies: Query<(), (With<Enemy>, InPlay)>,
+) {
+    if spawners.is_empty() || spawners.iter().any(|spawner| spawner.spawn_left > 0) {
+        return;
+    }
+    if !alive_enemies.is_empty() {
+        return;
+    }
+    commands.entity(*level_root).insert(LevelComplete);
+}
+
+fn detect_level_failed(
+    mut commands: Commands,
+    level_root: Single<
+        Entity,
+        (
+            With<LevelRoot>,
+            Without<LevelComplete>,
+            Without<LevelFailed>,
+        ),
+    >,
+    towers: Query<(), With<Tower>>,
+    standing_towers: Query<(), (With<Tower>, Without<Dead>)>,
+) {
+    if towers.is_empty() || !standing_towers.is_empty() {
+        return;
+    }
+    commands.entity(*level_root).insert(LevelFailed);
+}
+
+fn on_level_complete(
+    _trigger: Trigger<OnAdd, LevelComplete>,
+    current_level: Res<CurrentLevel>,
+    mut progress: ResMut<LevelProgress>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    let unlocked = progress.unlocked.max(current_level.0 + 2);
+    if unlocked != progress.unlocked {
+        progress.unlocked = unlocked;
+        progress.save();
+    }
+    next_pause.set(Pause(true));
+    next_menu.set(Menu::GameOver);
+}
+
+fn on_level_failed(
+    _trigger: Trigger<OnAdd, LevelFailed>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    next_pause.set(Pause(true));
+    next_menu.set(Menu::GameOver);
+}
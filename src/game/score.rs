@@ -0,0 +1,178 @@
+//! Points for killing enemies.
+//!
+//! Every enemy that [`Died`] is credited to whatever dealt the finishing blow: a spark, or a
+//! conduit when a redirected shot got it. Kills in quick succession build a combo and kills at
+//! the end of a long chain are worth more. Each kill pops its points up where it happened.
+
+use crate::game::effects::floating_text::floating_text;
+use crate::game::health::{Died, HealthSystems};
+use crate::game::pause_controller::{CosmeticSystems, PausableSystems};
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::screens::Screen;
+use crate::game::spark::Spark;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ScoreConfig {
+    #[default(100)]
+    pub kill_points: u32,
+    /// Kills less than this far apart keep the combo going.
+    #[default(2.0)]
+    pub combo_window_secs: f32,
+    /// Extra points per kill in the combo after the first.
+    #[default(0.25)]
+    pub combo_bonus: f32,
+    /// Extra points per chain hop after the first.
+    #[default(0.5)]
+    pub hop_bonus: f32,
+    #[default(1.0)]
+    pub popup_secs: f32,
+    /// How far popups rise over their lifetime.
+    #[default(30.0)]
+    pub popup_rise: f32,
+}
+
+impl ScoreConfig {
+    pub fn points(&self, combo: u32, hops: u32) -> u32 {
+        let multiplier = (1.0 + self.combo_bonus * combo.saturating_sub(1) as f32)
+            * (1.0 + self.hop_bonus * hops.saturating_sub(1) as f32);
+        (self.kill_points as f32 * multiplier).round() as u32
+    }
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct Score {
+    pub points: u64,
+    /// Kills in the running combo.
+    pub combo: u32,
+    pub best_combo: u32,
+    pub best_chain: u32,
+    last_kill_secs: Option<f32>,
+}
+
+impl Score {
+    /// Counts a kill at `now_secs` towards the combo and returns the combo it is part of.
+    pub fn combo_kill(&mut self, now_secs: f32, window_secs: f32) -> u32 {
+        self.combo = match self.last_kill_secs {
+            Some(last) if now_secs - last <= window_secs => self.combo + 1,
+            _ => 1,
+        };
+        self.last_kill_secs = Some(now_secs);
+        self.best_combo = self.best_combo.max(self.combo);
+        self.combo
+    }
+}
+
+/// An enemy killed and who gets the credit.
+#[auto_register_type]
+#[auto_add_event]
+#[derive(Event, Debug, Copy, Clone, Reflect)]
+pub struct Kill {
+    pub victim: Entity,
+    pub killer: Entity,
+    /// Set when the killer is a spark.
+    pub spark: Option<Entity>,
+    pub hops: u32,
+    pub combo: u32,
+    pub points: u32,
+    pub position: Vec3,
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        credit_kills.after(HealthSystems).in_set(PausableSystems),
+    );
+    app.add_systems(Update, spawn_popups.in_set(CosmeticSystems));
+    app.add_systems(OnEnter(Screen::Gameplay), |mut score: ResMut<Score>| {
+        *score = Score::default();
+    });
+}
+
+fn credit_kills(
+    time: Res<Time>,
+    config: Res<ScoreConfig>,
+    mut died: EventReader<Died>,
+    mut score: ResMut<Score>,
+    enemies: Query<&GlobalTransform, With<Enemy>>,
+    sparks: Query<(), With<Spark>>,
+    mut kills: EventWriter<Kill>,
+) {
+    let now = time.elapsed_secs();
+    if score.combo > 0
+        && score
+            .last_kill_secs
+            .is_some_and(|last| now - last > config.combo_window_secs)
+    {
+        score.combo = 0;
+    }
+    for event in died.read() {
+        // Enemies dying to nothing but time don't earn anything.
+        let Some(killer) = event.killer else {
+            continue;
+        };
+        let Ok(transform) = enemies.get(event.target) else {
+            continue;
+        };
+        let combo = score.combo_kill(now, config.combo_window_secs);
+        let points = config.points(combo, event.hops);
+        score.points += u64::from(points);
+        score.best_chain = score.best_chain.max(event.hops);
+        kills.write(Kill {
+            victim: event.target,
+            killer,
+            spark: sparks.contains(killer).then_some(killer),
+            hops: event.hops,
+            combo,
+            points,
+            position: transform.translation(),
+        });
+    }
+}
+
+fn spawn_popups(mut commands: Commands, config: Res<ScoreConfig>, mut kills: EventReader<Kill>) {
+    for kill in kills.read() {
+        let text = match kill.combo {
+            0 | 1 => format!("+{}", kill.points),
+            combo => format!("+{} x{combo}", kill.points),
+        };
+        commands.spawn(floating_text(
+            text,
+            Color::srgb(1.0, 0.85, 0.3),
+            20.0,
+            kill.position,
+            config.popup_rise,
+            config.popup_secs,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combos_and_chains_score_more() {
+        let config = ScoreConfig::default();
+        assert_eq!(config.points(1, 0), 100);
+        assert_eq!(config.points(1, 1), 100);
+        assert_eq!(config.points(3, 1), 150);
+        assert_eq!(config.points(1, 3), 200);
+
+        let mut score = Score::default();
+        assert_eq!(score.combo_kill(0.0, 2.0), 1);
+        assert_eq!(score.combo_kill(1.5, 2.0), 2);
+        assert_eq!(score.combo_kill(3.0, 2.0), 3);
+        assert_eq!(score.combo_kill(6.0, 2.0), 1);
+        assert_eq!(score.best_combo, 3);
+    }
+}
@@ -2,7 +2,9 @@
 
 use crate::game::menus::Menu;
 use crate::game::pause_controller::Pause;
-use crate::game::scenes::game::spawn_level;
+use crate::game::rng::seed::apply_selected_seed;
+use crate::game::rng::stream::fork_rng_streams;
+use crate::game::scenes::game::spawn_current_level;
 use crate::game::screens::Screen;
 use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 use bevy_auto_plugin::auto_plugin::*;
@@ -47,7 +49,10 @@ pub(super) fn plugin(app: &mut App) {
             *clear_color = ClearColor(GAMEPLAY_BACKGROUND_COLOR);
         },
     );
-    app.add_systems(OnEnter(Screen::Gameplay), spawn_level);
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (apply_selected_seed, fork_rng_streams, spawn_current_level).chain(),
+    );
 
     // Toggle pause on key press.
     app.add_systems(
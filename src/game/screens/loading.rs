@@ -4,7 +4,10 @@
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
-use crate::game::{asset_tracking::ResourceHandles, screens::Screen, theme::prelude::*};
+use crate::game::{
+    asset_tracking::ResourceHandles, prefabs::enemy_pool::enemy_pool_prewarmed, screens::Screen,
+    theme::prelude::*,
+};
 
 fn spawn_loading_screen(mut commands: Commands) {
     commands.spawn((
@@ -28,6 +31,10 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        enter_gameplay_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
+        enter_gameplay_screen.run_if(
+            in_state(Screen::Loading)
+                .and(all_assets_loaded)
+                .and(enemy_pool_prewarmed),
+        ),
     );
 }
@@ -0,0 +1,34 @@
+//! Gameplay runs in `FixedUpdate` so its outcome doesn't depend on the frame rate.
+//!
+//! Systems that decide what happens (damage, movement, spawning, AI) belong in the fixed
+//! schedule, those that only show it stay in `Update`. Physics interpolation smooths transforms
+//! between ticks.
+
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct SimulationConfig {
+    /// Gameplay ticks per second.
+    #[default(64.0)]
+    pub tick_rate_hz: f64,
+}
+
+fn apply_tick_rate(config: Res<SimulationConfig>, mut time: ResMut<Time<Fixed>>) {
+    // Zero would stall the simulation, keep the previous rate instead.
+    if config.tick_rate_hz > 0.0 {
+        time.set_timestep_hz(config.tick_rate_hz);
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        apply_tick_rate.run_if(resource_changed::<SimulationConfig>),
+    );
+}
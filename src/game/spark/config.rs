@@ -19,6 +19,31 @@ pub struct SparkConfig {
     pub damage_dealt_per_second: f32,
     #[default(50.0)]
     pub max_distance_jump_m: f32,
+    /// Jumps less than this far apart in time keep the chain going.
+    #[default(2.0)]
+    pub chain_window_secs: f32,
+    /// Radius of the bolt drawn for a jump, in world units.
+    /// Much thicker than the lightning ball bolts since a jump spans hundreds of units
+    /// and is read from the gameplay camera, not up close.
+    #[default(0.5)]
+    pub zap_bolt_width: f32,
+    #[default(0.25)]
+    pub zap_bolt_lifetime_secs: f32,
+    /// How fast a spark flies to its new target.
+    #[default(80.0)]
+    pub flight_speed_m_per_s: f32,
+    /// Height of the flight arc relative to the length of the jump.
+    #[default(0.25)]
+    pub arc_height_ratio: f32,
+    /// Gap left between a landed spark and the surface of its target.
+    #[default(1.0)]
+    pub attach_clearance: f32,
+    /// Jumps at least this long shake the screen.
+    #[default(25.0)]
+    pub big_zap_distance_m: f32,
+    /// Screen shake trauma added by a big zap.
+    #[default(0.5)]
+    pub big_zap_trauma: f32,
 }
 
 #[auto_plugin(app=app)]
@@ -1,14 +1,22 @@
 #![allow(unreachable_code)]
 
-mod config;
+pub(crate) mod config;
 
+use avian3d::prelude::{Collider, Position, Rotation};
 use bevy::prelude::*;
 use bevy_auto_plugin::auto_plugin::*;
 
-use crate::game::{constants::METERS_PER_UNIT, despawn::DespawnDelayed, health::Dead};
+use crate::game::{
+    camera::shake::AddTrauma,
+    constants::METERS_PER_UNIT,
+    despawn::DespawnDelayed,
+    effects::bolt::{Bolt, BoltStyle, bolt},
+    health::Dead,
+    spatial::{Nearby, SpatialIndexed},
+};
 
 use super::{
-    health::{AdjustHp, Health, MaxHealth},
+    health::{AdjustHp, DamageEvent, DamageType, Health, HealthSystems, MaxHealth},
     pause_controller::PausableSystems,
     snapshot::Snapshot,
 };
@@ -18,16 +26,40 @@ use config::*;
 #[auto_name]
 #[auto_register_type]
 #[derive(Component, Reflect)]
-#[require(Transform,Snapshot<GlobalTransform>)]
+#[require(Transform, Snapshot<GlobalTransform>, SpatialIndexed, SparkChain)]
 pub struct Spark;
 
+/// Jumps a spark made in quick succession, damage it deals counts as that many hops.
+#[auto_register_type]
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct SparkChain {
+    pub hops: u32,
+    last_hop_secs: Option<f32>,
+}
+
+/// A spark on its way to the target it is [`Zapping`], it attaches once it arrives.
+#[auto_register_type]
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct SparkFlight {
+    start: Vec3,
+    /// Landing spot in the local space of the target.
+    offset: Vec3,
+    /// Distance flown along the arc so far.
+    traveled: f32,
+    /// Positions after the last two fixed ticks, the transform eases between them.
+    previous: Vec3,
+    current: Vec3,
+}
+
 #[auto_register_type]
 #[derive(Component, Reflect)]
 #[require(Transform, Pickable)]
 pub struct SparkTarget;
 
 /// Spark -> Zapping -> SparkTarget
-/// Inserts ChildOf
+/// Inserts ChildOf once the spark has landed, see [`SparkFlight`]
 #[auto_register_type]
 #[derive(Component, Reflect)]
 #[require(Spark = enforce_exists!(Spark))]
@@ -48,14 +80,21 @@ pub fn plugin(app: &mut App) {
     app.add_observer(SparkTarget::handle_inserted)
         .add_observer(Zapping::handle_inserted)
         .add_observer(Zapping::handle_removed)
-        .add_observer(ZappedBy::handle_inserted)
+        .add_observer(ZappedBy::handle_death)
+        .add_observer(SparkChain::handle_hop)
+        .add_observer(SparkFlight::handle_zapping_inserted)
         .add_observer(Spark::handle_inserted);
 
     app.add_systems(
-        Update,
-        (spark::decay_health, spark::deal_dot).in_set(PausableSystems),
+        FixedUpdate,
+        (spark::fly, spark::decay_health, spark::deal_dot)
+            .in_set(PausableSystems)
+            .before(HealthSystems),
     );
 
+    // Sparks aren't rigid bodies, so physics interpolation doesn't smooth their flight.
+    app.add_systems(Update, spark::ease_flight.in_set(PausableSystems));
+
     app.add_systems(
         PostUpdate,
         spark::apply_distance_cost
@@ -69,7 +108,7 @@ impl SparkTarget {
         fn handle_clicked(
             tr: Trigger<Pointer<Click>>,
             mut commands: Commands,
-            sparks: Query<(Entity, &GlobalTransform), With<Spark>>,
+            sparks: Nearby<With<Spark>>,
             targets: Query<&GlobalTransform, With<SparkTarget>>,
             cfg: Res<SparkConfig>,
         ) {
@@ -78,12 +117,8 @@ impl SparkTarget {
                 .expect("cause of picking")
                 .translation();
 
-            for (spark, tf_spark) in sparks {
-                let dist = (tf_spark.translation() - tl_target).length() * METERS_PER_UNIT;
-                if dist > cfg.max_distance_jump_m {
-                    continue;
-                }
-
+            let max_distance = cfg.max_distance_jump_m / METERS_PER_UNIT;
+            for (spark, _) in sparks.within_radius(tl_target, max_distance) {
                 commands
                     .entity(spark)
                     .remove::<Zapping>()
@@ -95,17 +130,127 @@ impl SparkTarget {
     }
 }
 
+impl SparkChain {
+    /// Extends the chain if the last jump was recent enough, otherwise starts a new one.
+    pub fn hop(&mut self, now_secs: f32, window_secs: f32) {
+        self.hops = match self.last_hop_secs {
+            Some(last) if now_secs - last <= window_secs => self.hops + 1,
+            _ => 1,
+        };
+        self.last_hop_secs = Some(now_secs);
+    }
+
+    fn handle_hop(
+        tr: Trigger<OnInsert, Zapping>,
+        time: Res<Time>,
+        cfg: Res<SparkConfig>,
+        mut chains: Query<&mut Self>,
+    ) {
+        if let Ok(mut chain) = chains.get_mut(tr.target()) {
+            chain.hop(time.elapsed_secs(), cfg.chain_window_secs);
+        }
+    }
+}
+
+/// Point on a quadratic arc from `start` to `end` peaking `height` above the middle, `t` in 0..=1.
+pub fn arc_point(start: Vec3, end: Vec3, height: f32, t: f32) -> Vec3 {
+    let control = start.midpoint(end) + Vec3::Y * height * 2.0;
+    start.lerp(control, t).lerp(control.lerp(end, t), t)
+}
+
+/// `segments + 1` evenly spaced points along the [`arc_point`] arc, both ends included.
+pub fn arc_points(start: Vec3, end: Vec3, height: f32, segments: usize) -> Vec<Vec3> {
+    let segments = segments.max(1);
+    (0..=segments)
+        .map(|ix| arc_point(start, end, height, ix as f32 / segments as f32))
+        .collect()
+}
+
+/// Close estimate of the length of the [`arc_point`] arc.
+pub fn arc_length(start: Vec3, end: Vec3, height: f32) -> f32 {
+    let control = start.midpoint(end) + Vec3::Y * height * 2.0;
+    let chord = start.distance(end);
+    let net = start.distance(control) + control.distance(end);
+    (2.0 * chord + net) / 3.0
+}
+
+/// Straight segments the bolt drawn along a jump is made of.
+const JUMP_BOLT_SEGMENTS: usize = 16;
+
+impl SparkFlight {
+    /// Sends the spark off towards the side of the target facing it and draws the arc it flies
+    /// as a bolt.
+    fn handle_zapping_inserted(
+        tr: Trigger<OnInsert, Zapping>,
+        zapping: Query<&Zapping>,
+        transforms: Query<&GlobalTransform>,
+        children: Query<&Children>,
+        colliders: Query<(&Collider, &Position, &Rotation)>,
+        cfg: Res<SparkConfig>,
+        mut commands: Commands,
+    ) {
+        let spark = tr.target();
+        let Ok(Zapping(target)) = zapping.get(spark) else {
+            return;
+        };
+        let (Ok(from), Ok(to)) = (transforms.get(spark), transforms.get(*target)) else {
+            commands
+                .entity(spark)
+                .insert((ChildOf(*target), Transform::default()));
+            return;
+        };
+        let start = from.translation();
+        let origin = to.translation();
+        // The closest point on any collider of the target, its origin if it has none.
+        let surface = std::iter::once(*target)
+            .chain(children.iter_descendants(*target))
+            .filter_map(|entity| colliders.get(entity).ok())
+            .map(|(collider, position, rotation)| {
+                collider.project_point(*position, *rotation, start, false).0
+            })
+            .min_by(|a, b| {
+                a.distance_squared(start)
+                    .total_cmp(&b.distance_squared(start))
+            })
+            .unwrap_or(origin);
+        let landing = surface + (surface - origin).normalize_or_zero() * cfg.attach_clearance;
+        // Same arc as `spark::fly`, the target may still move away from it during the flight.
+        let height = start.distance(landing) * cfg.arc_height_ratio;
+        commands.spawn(bolt(
+            Bolt::new(arc_points(start, landing, height, JUMP_BOLT_SEGMENTS)),
+            BoltStyle {
+                width: cfg.zap_bolt_width,
+                ..Default::default()
+            },
+            cfg.zap_bolt_lifetime_secs,
+            0.0,
+        ));
+        commands.entity(spark).insert(SparkFlight {
+            start,
+            offset: to.affine().inverse().transform_point3(landing),
+            traveled: 0.0,
+            previous: start,
+            current: start,
+        });
+    }
+}
+
 impl Zapping {
-    /// Updates parenting
+    /// Shakes the screen on big jumps
     fn handle_inserted(
         tr: Trigger<OnInsert, Self>,
         comp: Query<&Self, Added<Self>>,
-        mut commands: Commands,
+        transforms: Query<&GlobalTransform>,
+        cfg: Res<SparkConfig>,
+        mut trauma: EventWriter<AddTrauma>,
     ) {
         let comp = comp.get(tr.target()).expect("OnInsert broken");
-        commands
-            .entity(tr.target())
-            .insert((ChildOf(comp.0), Transform::default())); // TODO better relative positioning
+        if let (Ok(from), Ok(to)) = (transforms.get(tr.target()), transforms.get(comp.0)) {
+            let dist = (to.translation() - from.translation()).length() * METERS_PER_UNIT;
+            if dist >= cfg.big_zap_distance_m {
+                trauma.write(AddTrauma(cfg.big_zap_trauma));
+            }
+        }
     }
 
     fn handle_removed(
@@ -116,17 +261,22 @@ impl Zapping {
         let (mut tf, gl_tf) = sparks.get_mut(tr.target()).expect("require");
         tf.translation = gl_tf.translation();
 
-        commands.entity(tr.target()).try_remove::<ChildOf>();
+        commands
+            .entity(tr.target())
+            .try_remove::<(ChildOf, SparkFlight)>();
     }
 }
 
 impl ZappedBy {
-    fn handle_inserted(tr: Trigger<OnInsert, Self>, mut commands: Commands) {
-        fn handle_death(tr: Trigger<OnInsert, Dead>, mut commands: Commands) {
-            commands.entity(tr.target()).remove::<ZappedBy>();
+    /// Sparks let go of whatever died under them, one observer for every target.
+    fn handle_death(
+        tr: Trigger<OnInsert, Dead>,
+        zapped: Query<(), With<Self>>,
+        mut commands: Commands,
+    ) {
+        if zapped.contains(tr.target()) {
+            commands.entity(tr.target()).remove::<Self>();
         }
-
-        commands.entity(tr.target()).observe(handle_death);
     }
 }
 
@@ -144,7 +294,7 @@ impl Spark {
 }
 
 #[allow(clippy::module_inception)]
-mod spark {
+pub(crate) mod spark {
     use super::*;
 
     pub fn decay_health(
@@ -163,18 +313,68 @@ mod spark {
     }
 
     pub fn deal_dot(
-        targets: Query<Entity, (With<ZappedBy>, With<Health>, Without<Dead>)>,
+        targets: Query<(Entity, &ZappedBy), (With<Health>, Without<Dead>)>,
+        // Sparks only deal damage once they have landed.
+        landed: Query<&SparkChain, Without<SparkFlight>>,
         time: Res<Time>,
-        mut adjust_hp_event: EventWriter<AdjustHp>,
+        mut damage_event: EventWriter<DamageEvent>,
         cfg: Res<SparkConfig>,
     ) {
         let damage_amount = time.delta_secs() * cfg.damage_dealt_per_second;
 
-        adjust_hp_event.write_batch(
-            targets
+        // Credited to the spark that jumped on last.
+        damage_event.write_batch(targets.iter().filter_map(|(target, zapped_by)| {
+            let (spark, chain) = zapped_by
+                .0
                 .iter()
-                .map(|target| AdjustHp::new(target, -damage_amount)),
-        );
+                .rev()
+                .find_map(|&spark| Some((spark, landed.get(spark).ok()?)))?;
+            Some(
+                DamageEvent::new(target, damage_amount, DamageType::Electric)
+                    .with_source(spark)
+                    .with_hops(chain.hops),
+            )
+        }));
+    }
+
+    /// Moves flying sparks along their arc, the target may move in the meantime.
+    pub fn fly(
+        mut commands: Commands,
+        time: Res<Time>,
+        cfg: Res<SparkConfig>,
+        mut sparks: Query<(Entity, &Zapping, &mut SparkFlight)>,
+        targets: Query<&GlobalTransform>,
+    ) {
+        let speed = cfg.flight_speed_m_per_s / METERS_PER_UNIT;
+        for (spark, Zapping(target), mut flight) in sparks.iter_mut() {
+            let Ok(to) = targets.get(*target) else {
+                continue;
+            };
+            let end = to.transform_point(flight.offset);
+            let height = flight.start.distance(end) * cfg.arc_height_ratio;
+            flight.traveled += speed * time.delta_secs();
+            let t = flight.traveled / arc_length(flight.start, end, height).max(f32::EPSILON);
+            if t < 1.0 {
+                flight.previous = flight.current;
+                flight.current = arc_point(flight.start, end, height, t);
+                continue;
+            }
+            commands
+                .entity(spark)
+                .remove::<SparkFlight>()
+                .insert((ChildOf(*target), Transform::from_translation(flight.offset)));
+        }
+    }
+
+    /// Places flying sparks between their last two fixed tick positions.
+    pub fn ease_flight(
+        fixed_time: Res<Time<Fixed>>,
+        mut sparks: Query<(&SparkFlight, &mut Transform)>,
+    ) {
+        let fraction = fixed_time.overstep_fraction();
+        for (flight, mut transform) in sparks.iter_mut() {
+            transform.translation = flight.previous.lerp(flight.current, fraction);
+        }
     }
 
     pub fn apply_distance_cost(
@@ -196,3 +396,27 @@ mod spark {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arc_goes_over_the_middle() {
+        let start = Vec3::ZERO;
+        let end = Vec3::new(10.0, 0.0, 0.0);
+        assert_eq!(arc_point(start, end, 2.0, 0.0), start);
+        assert_eq!(arc_point(start, end, 2.0, 1.0), end);
+        assert_eq!(arc_point(start, end, 2.0, 0.5), Vec3::new(5.0, 2.0, 0.0));
+        assert_eq!(arc_length(start, end, 0.0), 10.0);
+        let length = arc_length(start, end, 2.0);
+        assert!(length > 10.0 && length < 12.0);
+
+        let points = arc_points(start, end, 2.0, 4);
+        assert_eq!(points.len(), 5);
+        assert_eq!(
+            (points[0], points[2], points[4]),
+            (start, Vec3::new(5.0, 2.0, 0.0), end)
+        );
+    }
+}
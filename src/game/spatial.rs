@@ -0,0 +1,286 @@
+//! Uniform grid of [`SpatialIndexed`] entities for radius and nearest neighbor queries.
+//!
+//! The index follows [`GlobalTransform`] changes after transform propagation, so queries made
+//! during `Update` see last frame's positions. Use [`Nearby`] to query it for entities matching a
+//! filter.
+
+use bevy::ecs::query::QueryFilter;
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy_auto_plugin::auto_plugin::*;
+
+/// Keeps the entity in the [`SpatialIndex`].
+#[auto_register_type]
+#[derive(Component, Debug, Default, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub struct SpatialIndexed;
+
+/// Default edge length of a grid cell, roughly the distance most queries look at.
+pub const DEFAULT_CELL_SIZE: f32 = 40.0;
+
+#[derive(Resource, Debug)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<IVec3, Vec<Entity>>,
+    entries: HashMap<Entity, (IVec3, Vec3)>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: HashMap::default(),
+            entries: HashMap::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn position(&self, entity: Entity) -> Option<Vec3> {
+        self.entries.get(&entity).map(|&(_, position)| position)
+    }
+
+    fn cell(&self, position: Vec3) -> IVec3 {
+        (position / self.cell_size).floor().as_ivec3()
+    }
+
+    /// Adds the entity or moves it to `position`.
+    pub fn insert(&mut self, entity: Entity, position: Vec3) {
+        let cell = self.cell(position);
+        match self.entries.insert(entity, (cell, position)) {
+            Some((old_cell, _)) if old_cell == cell => return,
+            Some((old_cell, _)) => self.remove_from_cell(old_cell, entity),
+            None => {}
+        }
+        self.cells.entry(cell).or_default().push(entity);
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some((cell, _)) = self.entries.remove(&entity) {
+            self.remove_from_cell(cell, entity);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    fn remove_from_cell(&mut self, cell: IVec3, entity: Entity) {
+        let Some(entities) = self.cells.get_mut(&cell) else {
+            return;
+        };
+        if let Some(ix) = entities.iter().position(|&other| other == entity) {
+            entities.swap_remove(ix);
+        }
+        if entities.is_empty() {
+            self.cells.remove(&cell);
+        }
+    }
+
+    /// Entities within `radius` of `center`, in no particular order.
+    pub fn within_radius(
+        &self,
+        center: Vec3,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec3)> + '_ {
+        let min = self.cell(center - radius);
+        let max = self.cell(center + radius);
+        let radius_squared = radius * radius;
+        (min.x..=max.x)
+            .flat_map(move |x| {
+                (min.y..=max.y).flat_map(move |y| (min.z..=max.z).map(move |z| IVec3::new(x, y, z)))
+            })
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter_map(move |&entity| {
+                let (_, position) = self.entries[&entity];
+                (position.distance_squared(center) <= radius_squared).then_some((entity, position))
+            })
+    }
+
+    /// Up to `k` entities accepted by `filter`, closest to `center` first.
+    pub fn k_nearest(
+        &self,
+        center: Vec3,
+        k: usize,
+        filter: impl Fn(Entity) -> bool,
+    ) -> Vec<(Entity, Vec3)> {
+        if k == 0 || self.is_empty() {
+            return Vec::new();
+        }
+        // Past this radius every occupied cell has been looked at.
+        let max_radius = self
+            .cells
+            .keys()
+            .map(|cell| {
+                let cell_min = cell.as_vec3() * self.cell_size;
+                let cell_max = cell_min + self.cell_size;
+                (center - cell_min)
+                    .abs()
+                    .max((center - cell_max).abs())
+                    .length()
+            })
+            .fold(0.0, f32::max);
+
+        let mut radius = self.cell_size;
+        loop {
+            let mut found = self
+                .within_radius(center, radius)
+                .filter(|&(entity, _)| filter(entity))
+                .collect::<Vec<_>>();
+            // Anything outside the radius is further away than all of these.
+            if found.len() >= k || radius >= max_radius {
+                found.sort_by(|(_, a), (_, b)| {
+                    a.distance_squared(center)
+                        .total_cmp(&b.distance_squared(center))
+                });
+                found.truncate(k);
+                return found;
+            }
+            radius *= 2.0;
+        }
+    }
+
+    pub fn nearest(&self, center: Vec3, filter: impl Fn(Entity) -> bool) -> Option<(Entity, Vec3)> {
+        self.k_nearest(center, 1, filter).into_iter().next()
+    }
+}
+
+/// [`SpatialIndex`] queries limited to entities matching `F`.
+#[derive(SystemParam)]
+pub struct Nearby<'w, 's, F: QueryFilter + 'static> {
+    index: Res<'w, SpatialIndex>,
+    filter: Query<'w, 's, (), F>,
+}
+
+impl<F: QueryFilter + 'static> Nearby<'_, '_, F> {
+    pub fn within_radius(
+        &self,
+        center: Vec3,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec3)> + '_ {
+        self.index
+            .within_radius(center, radius)
+            .filter(|&(entity, _)| self.filter.contains(entity))
+    }
+
+    pub fn k_nearest(&self, center: Vec3, k: usize) -> Vec<(Entity, Vec3)> {
+        self.index
+            .k_nearest(center, k, |entity| self.filter.contains(entity))
+    }
+
+    pub fn nearest(&self, center: Vec3) -> Option<(Entity, Vec3)> {
+        self.index
+            .nearest(center, |entity| self.filter.contains(entity))
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<SpatialIndex>();
+    app.add_observer(on_indexed_removed);
+    app.add_systems(
+        PostUpdate,
+        update_index.after(TransformSystem::TransformPropagate),
+    );
+}
+
+fn update_index(
+    mut index: ResMut<SpatialIndex>,
+    moved: Query<(Entity, &GlobalTransform), (With<SpatialIndexed>, Changed<GlobalTransform>)>,
+) {
+    for (entity, transform) in moved.iter() {
+        index.insert(entity, transform.translation());
+    }
+}
+
+fn on_indexed_removed(trigger: Trigger<OnRemove, SpatialIndexed>, mut index: ResMut<SpatialIndex>) {
+    index.remove(trigger.target());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> (SpatialIndex, Vec<(Entity, Vec3)>) {
+        let mut index = SpatialIndex::new(10.0);
+        let points = (0..200)
+            .map(|ix| {
+                let ix = ix as f32;
+                let position = Vec3::new(
+                    (ix * 7.3) % 97.0 - 48.0,
+                    (ix * 3.1) % 11.0,
+                    (ix * 13.7) % 89.0 - 44.0,
+                );
+                (Entity::from_raw(ix as u32), position)
+            })
+            .collect::<Vec<_>>();
+        for &(entity, position) in &points {
+            index.insert(entity, position);
+        }
+        (index, points)
+    }
+
+    #[test]
+    fn radius_matches_brute_force() {
+        let (index, points) = grid();
+        let center = Vec3::new(3.0, 2.0, -7.0);
+        let mut found = index
+            .within_radius(center, 25.0)
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        found.sort();
+        let expected = points
+            .iter()
+            .filter(|(_, position)| position.distance(center) <= 25.0)
+            .map(|&(entity, _)| entity)
+            .collect::<Vec<_>>();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn k_nearest_matches_brute_force() {
+        let (index, mut points) = grid();
+        let center = Vec3::new(60.0, 0.0, 60.0);
+        let even = |entity: Entity| entity.index() % 2 == 0;
+        let found = index.k_nearest(center, 5, even);
+        points.retain(|&(entity, _)| even(entity));
+        points.sort_by(|(_, a), (_, b)| {
+            a.distance_squared(center)
+                .total_cmp(&b.distance_squared(center))
+        });
+        assert_eq!(found, points[..5]);
+        assert_eq!(index.k_nearest(center, 500, even).len(), points.len());
+    }
+
+    #[test]
+    fn moves_and_removes() {
+        let mut index = SpatialIndex::new(10.0);
+        let entity = Entity::from_raw(1);
+        index.insert(entity, Vec3::ZERO);
+        index.insert(entity, Vec3::splat(100.0));
+        assert_eq!(index.within_radius(Vec3::ZERO, 5.0).count(), 0);
+        assert_eq!(
+            index.nearest(Vec3::ZERO, |_| true).map(|(e, _)| e),
+            Some(entity)
+        );
+        index.remove(entity);
+        assert!(index.is_empty());
+        assert!(index.nearest(Vec3::ZERO, |_| true).is_none());
+    }
+}
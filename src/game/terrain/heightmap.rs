@@ -0,0 +1,295 @@
+//! Seeded heightmap generation.
+//!
+//! Heights come from fractal value noise. Paths and clearings are carved afterwards by
+//! blending the height towards a flat level near them.
+
+use crate::game::rng::Prng;
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use smart_default::SmartDefault;
+
+/// Grid of heights covering a `size` rectangle on the XZ plane centered on the origin.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct Heightmap {
+    size: Vec2,
+    /// Vertices per side.
+    resolution: usize,
+    /// Row major, rows along Z and columns along X.
+    heights: Vec<f32>,
+}
+
+impl Heightmap {
+    pub fn new(size: Vec2, resolution: usize) -> Self {
+        let resolution = resolution.max(2);
+        Self {
+            size,
+            resolution,
+            heights: vec![0.0; resolution * resolution],
+        }
+    }
+
+    /// Fills the heightmap with fractal value noise in the `0..=max_height` range.
+    pub fn fill_noise(&mut self, rng: &mut Prng, noise: &NoiseSettings) {
+        let value_noise = ValueNoise::new(rng);
+        for iz in 0..self.resolution {
+            for ix in 0..self.resolution {
+                let p = self.vertex_xz(ix, iz);
+                let value = value_noise.fbm(p * noise.frequency, noise);
+                self.heights[iz * self.resolution + ix] = (value * 0.5 + 0.5) * noise.max_height;
+            }
+        }
+    }
+
+    /// Flattens the terrain to `height` along the segment from `a` to `b`.
+    /// Within `radius` the terrain is fully flattened, over the next `falloff` it blends back.
+    pub fn carve_segment(&mut self, a: Vec2, b: Vec2, height: f32, radius: f32, falloff: f32) {
+        for iz in 0..self.resolution {
+            for ix in 0..self.resolution {
+                let p = self.vertex_xz(ix, iz);
+                let distance = distance_to_segment(p, a, b);
+                let t = ((distance - radius) / falloff.max(f32::EPSILON)).clamp(0.0, 1.0);
+                let blend = t * t * (3.0 - 2.0 * t);
+                let current = &mut self.heights[iz * self.resolution + ix];
+                *current = height.lerp(*current, blend);
+            }
+        }
+    }
+
+    /// Flattens a circular area, see [`Self::carve_segment`].
+    pub fn carve_circle(&mut self, center: Vec2, height: f32, radius: f32, falloff: f32) {
+        self.carve_segment(center, center, height, radius, falloff);
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    pub fn contains(&self, xz: Vec2) -> bool {
+        (xz.abs() * 2.0).cmple(self.size).all()
+    }
+
+    fn cell_size(&self) -> Vec2 {
+        self.size / (self.resolution - 1) as f32
+    }
+
+    fn vertex_xz(&self, ix: usize, iz: usize) -> Vec2 {
+        Vec2::new(ix as f32, iz as f32) * self.cell_size() - self.size / 2.0
+    }
+
+    fn height(&self, ix: usize, iz: usize) -> f32 {
+        let last = self.resolution - 1;
+        self.heights[iz.min(last) * self.resolution + ix.min(last)]
+    }
+
+    /// Bilinearly interpolated height at a world XZ position, clamped to the edges.
+    pub fn height_at(&self, xz: Vec2) -> f32 {
+        let grid = ((xz + self.size / 2.0) / self.cell_size())
+            .clamp(Vec2::ZERO, Vec2::splat((self.resolution - 1) as f32));
+        let (ix, iz) = (grid.x as usize, grid.y as usize);
+        let fract = grid - Vec2::new(ix as f32, iz as f32);
+        let top = self.height(ix, iz).lerp(self.height(ix + 1, iz), fract.x);
+        let bottom = self
+            .height(ix, iz + 1)
+            .lerp(self.height(ix + 1, iz + 1), fract.x);
+        top.lerp(bottom, fract.y)
+    }
+
+    /// Heights laid out for `avian3d::prelude::Collider::heightfield`, outer index along X.
+    pub fn collider_heights(&self) -> Vec<Vec<f32>> {
+        (0..self.resolution)
+            .map(|ix| (0..self.resolution).map(|iz| self.height(ix, iz)).collect())
+            .collect()
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        let resolution = self.resolution;
+        let cell_size = self.cell_size();
+        let mut positions = Vec::with_capacity(resolution * resolution);
+        let mut normals = Vec::with_capacity(resolution * resolution);
+        let mut uvs = Vec::with_capacity(resolution * resolution);
+        for iz in 0..resolution {
+            for ix in 0..resolution {
+                let xz = self.vertex_xz(ix, iz);
+                positions.push([xz.x, self.height(ix, iz), xz.y]);
+
+                let left = self.height(ix.saturating_sub(1), iz);
+                let right = self.height(ix + 1, iz);
+                let back = self.height(ix, iz.saturating_sub(1));
+                let front = self.height(ix, iz + 1);
+                let normal = Vec3::new(
+                    (left - right) / cell_size.x,
+                    2.0,
+                    (back - front) / cell_size.y,
+                )
+                .normalize();
+                normals.push(normal.to_array());
+
+                uvs.push([
+                    ix as f32 / (resolution - 1) as f32,
+                    iz as f32 / (resolution - 1) as f32,
+                ]);
+            }
+        }
+
+        let mut indices = Vec::with_capacity((resolution - 1) * (resolution - 1) * 6);
+        for iz in 0..resolution as u32 - 1 {
+            for ix in 0..resolution as u32 - 1 {
+                let i = iz * resolution as u32 + ix;
+                let below = i + resolution as u32;
+                indices.extend_from_slice(&[i, below, i + 1, i + 1, below, below + 1]);
+            }
+        }
+
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+    }
+}
+
+#[derive(Debug, SmartDefault, Copy, Clone, PartialEq, Reflect)]
+pub struct NoiseSettings {
+    /// Scale applied to world coordinates before sampling the noise.
+    #[default(0.006)]
+    pub frequency: f32,
+    #[default(5)]
+    pub octaves: u32,
+    /// Frequency multiplier for each octave.
+    #[default(2.0)]
+    pub lacunarity: f32,
+    /// Amplitude multiplier for each octave.
+    #[default(0.5)]
+    pub persistence: f32,
+    #[default(40.0)]
+    pub max_height: f32,
+}
+
+/// Smoothly interpolated random values on an integer lattice.
+struct ValueNoise {
+    values: [f32; 256],
+    permutation: [u8; 256],
+}
+
+impl ValueNoise {
+    fn new(rng: &mut Prng) -> Self {
+        let mut values = [0.0; 256];
+        for value in values.iter_mut() {
+            *value = rng.random_range(-1.0..=1.0);
+        }
+        let mut permutation = [0u8; 256];
+        for (ix, p) in permutation.iter_mut().enumerate() {
+            *p = ix as u8;
+        }
+        permutation.shuffle(rng);
+        Self {
+            values,
+            permutation,
+        }
+    }
+
+    fn lattice(&self, x: i32, z: i32) -> f32 {
+        let hash = self.permutation[(x & 255) as usize] as usize;
+        let hash = self.permutation[(hash + (z & 255) as usize) & 255] as usize;
+        self.values[hash]
+    }
+
+    fn sample(&self, p: Vec2) -> f32 {
+        let cell = p.floor();
+        let (x, z) = (cell.x as i32, cell.y as i32);
+        let t = p - cell;
+        let t = t * t * (Vec2::splat(3.0) - 2.0 * t);
+        let top = self.lattice(x, z).lerp(self.lattice(x + 1, z), t.x);
+        let bottom = self.lattice(x, z + 1).lerp(self.lattice(x + 1, z + 1), t.x);
+        top.lerp(bottom, t.y)
+    }
+
+    /// Fractal sum of octaves normalized to `-1..=1`.
+    fn fbm(&self, p: Vec2, settings: &NoiseSettings) -> f32 {
+        let mut sum = 0.0;
+        let mut norm = 0.0;
+        let mut amplitude = 1.0;
+        let mut p = p;
+        for _ in 0..settings.octaves.max(1) {
+            sum += self.sample(p) * amplitude;
+            norm += amplitude;
+            amplitude *= settings.persistence;
+            p *= settings.lacunarity;
+        }
+        sum / norm
+    }
+}
+
+pub fn distance_to_segment(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared < f32::EPSILON {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    p.distance(a + ab * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn noise_heightmap(seed: u64) -> Heightmap {
+        let mut heightmap = Heightmap::new(Vec2::splat(500.0), 33);
+        heightmap.fill_noise(&mut Prng::seed_from_u64(seed), &NoiseSettings::default());
+        heightmap
+    }
+
+    #[test]
+    fn noise_follows_seed() {
+        assert_eq!(noise_heightmap(1).heights, noise_heightmap(1).heights);
+        assert_ne!(noise_heightmap(1).heights, noise_heightmap(2).heights);
+    }
+
+    #[test]
+    fn segment_distance() {
+        let (a, b) = (Vec2::ZERO, Vec2::new(10.0, 0.0));
+        assert_eq!(distance_to_segment(Vec2::new(5.0, 3.0), a, b), 3.0);
+        assert_eq!(distance_to_segment(Vec2::new(-4.0, 3.0), a, b), 5.0);
+        assert_eq!(distance_to_segment(Vec2::new(13.0, 4.0), a, b), 5.0);
+        // Degenerate segments are points.
+        assert_eq!(distance_to_segment(Vec2::new(3.0, 4.0), a, a), 5.0);
+    }
+
+    #[test]
+    fn carving_flattens_the_path() {
+        let mut heightmap = noise_heightmap(4);
+        let (a, b) = (Vec2::new(-200.0, 0.0), Vec2::new(200.0, 0.0));
+        heightmap.carve_segment(a, b, 0.0, 20.0, 30.0);
+        for x in [-200.0, -50.0, 0.0, 120.0, 200.0] {
+            assert_eq!(heightmap.height_at(Vec2::new(x, 0.0)), 0.0);
+        }
+        // Beyond the falloff the noise is left alone.
+        let untouched = noise_heightmap(4);
+        let far = Vec2::new(0.0, 200.0);
+        assert_eq!(heightmap.height_at(far), untouched.height_at(far));
+    }
+
+    #[test]
+    fn collider_heights_are_indexed_by_x_first() {
+        let mut heightmap = Heightmap::new(Vec2::splat(10.0), 3);
+        // Slopes up along X only.
+        for iz in 0..3 {
+            for ix in 0..3 {
+                heightmap.heights[iz * 3 + ix] = ix as f32;
+            }
+        }
+        let heights = heightmap.collider_heights();
+        assert_eq!(heights[2], vec![2.0; 3]);
+        assert_eq!(heights[0][2], 0.0);
+        assert_eq!(heightmap.height_at(Vec2::new(5.0, -5.0)), 2.0);
+    }
+}
@@ -0,0 +1,289 @@
+//! Procedural terrain the levels are built on.
+
+pub mod heightmap;
+pub mod poisson;
+
+use crate::game::rng::{Prng, Seed, choice::ShuffleBag};
+use crate::game::screens::Screen;
+use avian3d::prelude::{Collider, RigidBody};
+use bevy::color::palettes::css::{DARK_GRAY, DARK_GREEN, GREEN, SADDLE_BROWN};
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use heightmap::{Heightmap, NoiseSettings, distance_to_segment};
+use poisson::poisson_disk;
+use rand::{Rng, SeedableRng};
+use smart_default::SmartDefault;
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct TerrainConfig {
+    /// Heightmap vertices per side.
+    #[default(129)]
+    pub resolution: usize,
+    pub noise: NoiseSettings,
+    /// Height paths and clearings are flattened to.
+    #[default(0.0)]
+    pub path_height: f32,
+    /// Half width of the fully flat part of a path.
+    #[default(15.0)]
+    pub path_radius: f32,
+    /// Distance over which a path blends back into the terrain.
+    #[default(25.0)]
+    pub path_falloff: f32,
+    /// Radius of the flat area around the tower.
+    #[default(60.0)]
+    pub tower_clearing_radius: f32,
+    /// Radius of the flat area around each spawner.
+    #[default(20.0)]
+    pub spawner_clearing_radius: f32,
+    /// Minimum distance between props.
+    #[default(45.0)]
+    pub prop_min_distance: f32,
+}
+
+#[auto_register_type]
+#[auto_name]
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Transform, Visibility)]
+pub struct Terrain;
+
+#[auto_register_type]
+#[auto_name]
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+#[require(Transform, Visibility)]
+#[require(Pickable = Pickable::IGNORE)]
+pub enum Prop {
+    Rock,
+    Tree,
+}
+
+#[auto_register_type]
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+struct PropAssets {
+    rock_mesh: Handle<Mesh>,
+    rock_material: Handle<StandardMaterial>,
+    trunk_mesh: Handle<Mesh>,
+    trunk_material: Handle<StandardMaterial>,
+    leaves_mesh: Handle<Mesh>,
+    leaves_material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for PropAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let rock_mesh = meshes.add(Sphere::new(1.0).mesh().ico(1).expect("valid subdivisions"));
+        let trunk_mesh = meshes.add(Cylinder::new(0.2, 1.0));
+        let leaves_mesh = meshes.add(Cone::new(1.0, 2.0));
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let mut material = |color: Color| {
+            materials.add(StandardMaterial {
+                base_color: color,
+                perceptual_roughness: 1.0,
+                ..Default::default()
+            })
+        };
+        Self {
+            rock_mesh,
+            rock_material: material(Color::from(DARK_GRAY)),
+            trunk_mesh,
+            trunk_material: material(Color::from(SADDLE_BROWN)),
+            leaves_mesh,
+            leaves_material: material(Color::from(DARK_GREEN)),
+        }
+    }
+}
+
+/// Area props are kept out of.
+pub enum KeepClear {
+    Segment(Vec2, Vec2, f32),
+    Circle(Vec2, f32),
+}
+
+impl KeepClear {
+    fn contains(&self, p: Vec2) -> bool {
+        match *self {
+            Self::Segment(a, b, radius) => distance_to_segment(p, a, b) < radius,
+            Self::Circle(center, radius) => p.distance(center) < radius,
+        }
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<Heightmap>();
+    app.init_resource::<PropAssets>();
+    app.add_observer(on_prop_added);
+    app.add_systems(OnExit(Screen::Gameplay), |mut commands: Commands| {
+        commands.remove_resource::<Heightmap>();
+    });
+}
+
+/// Mixes the level index into the run seed so every level gets its own terrain.
+pub fn level_seed(seed: Seed, level: usize) -> Seed {
+    u64::from_le_bytes(seed)
+        .wrapping_add((level as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .to_le_bytes()
+}
+
+/// Seeds for the generation steps, so they don't replay each other's rolls.
+const HEIGHTMAP_STREAM: u64 = 1;
+const PROPS_STREAM: u64 = 2;
+
+/// Derives the seed of one generation step from the level seed.
+fn stream_seed(seed: Seed, stream: u64) -> Seed {
+    (u64::from_le_bytes(seed) ^ stream.wrapping_mul(0xBF58_476D_1CE4_E5B9)).to_le_bytes()
+}
+
+/// Connects every spawner to its nearest tower.
+pub fn paths<'a>(
+    spawners: &'a [Vec2],
+    towers: &'a [Vec2],
+) -> impl Iterator<Item = (Vec2, Vec2)> + 'a {
+    spawners.iter().filter_map(|&spawner| {
+        let tower = towers.iter().copied().min_by(|a, b| {
+            a.distance_squared(spawner)
+                .total_cmp(&b.distance_squared(spawner))
+        })?;
+        Some((spawner, tower))
+    })
+}
+
+/// Generates the heightmap with flat [`paths`] carved from every spawner to a tower.
+pub fn generate_heightmap(
+    seed: Seed,
+    size: Vec2,
+    towers: &[Vec2],
+    spawners: &[Vec2],
+    config: &TerrainConfig,
+) -> Heightmap {
+    let mut rng = Prng::from_seed(stream_seed(seed, HEIGHTMAP_STREAM));
+    let mut heightmap = Heightmap::new(size, config.resolution);
+    heightmap.fill_noise(&mut rng, &config.noise);
+    for (spawner, tower) in paths(spawners, towers) {
+        heightmap.carve_segment(
+            spawner,
+            tower,
+            config.path_height,
+            config.path_radius,
+            config.path_falloff,
+        );
+    }
+    for &spawner in spawners {
+        heightmap.carve_circle(
+            spawner,
+            config.path_height,
+            config.spawner_clearing_radius,
+            config.path_falloff,
+        );
+    }
+    for &tower in towers {
+        heightmap.carve_circle(
+            tower,
+            config.path_height,
+            config.tower_clearing_radius,
+            config.path_falloff,
+        );
+    }
+    heightmap
+}
+
+pub fn terrain(
+    heightmap: &Heightmap,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) -> impl Bundle {
+    let size = heightmap.size();
+    (
+        Terrain,
+        Mesh3d(meshes.add(heightmap.mesh())),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::from(GREEN),
+            perceptual_roughness: 1.0,
+            reflectance: 0.0,
+            ..Default::default()
+        })),
+        Collider::heightfield(heightmap.collider_heights(), Vec3::new(size.x, 1.0, size.y)),
+        RigidBody::Static,
+    )
+}
+
+/// Scatters props over the terrain, outside of the `keep_clear` areas.
+pub fn props(
+    seed: Seed,
+    heightmap: &Heightmap,
+    keep_clear: &[KeepClear],
+    config: &TerrainConfig,
+) -> Vec<(Prop, Transform)> {
+    let mut rng = Prng::from_seed(stream_seed(seed, PROPS_STREAM));
+    // 3 rocks for every 7 trees, without clumps of either.
+    let mut kinds = ShuffleBag::new([Prop::Rock; 3].into_iter().chain([Prop::Tree; 7]).collect());
+    poisson_disk(&mut rng, heightmap.size(), config.prop_min_distance)
+        .into_iter()
+        .filter(|&p| heightmap.contains(p) && !keep_clear.iter().any(|area| area.contains(p)))
+        .map(|p| {
+            let prop = *kinds.draw(&mut rng).expect("bag is not empty");
+            let transform = Transform::from_translation(p.extend(heightmap.height_at(p)).xzy())
+                .with_rotation(Quat::from_rotation_y(
+                    rng.random_range(0.0..std::f32::consts::TAU),
+                ))
+                .with_scale(Vec3::splat(rng.random_range(4.0..10.0)));
+            (prop, transform)
+        })
+        .collect()
+}
+
+fn on_prop_added(
+    trigger: Trigger<OnAdd, Prop>,
+    props: Query<&Prop>,
+    assets: Res<PropAssets>,
+    mut commands: Commands,
+) {
+    let prop = props
+        .get(trigger.target())
+        .expect("No target entity for trigger");
+    match prop {
+        Prop::Rock => {
+            commands.entity(trigger.target()).insert((
+                Mesh3d(assets.rock_mesh.clone()),
+                MeshMaterial3d(assets.rock_material.clone()),
+            ));
+        }
+        Prop::Tree => {
+            commands.entity(trigger.target()).insert(children![
+                (
+                    Mesh3d(assets.trunk_mesh.clone()),
+                    MeshMaterial3d(assets.trunk_material.clone()),
+                    Transform::from_xyz(0.0, 0.5, 0.0),
+                    Pickable::IGNORE,
+                ),
+                (
+                    Mesh3d(assets.leaves_mesh.clone()),
+                    MeshMaterial3d(assets.leaves_material.clone()),
+                    Transform::from_xyz(0.0, 2.0, 0.0),
+                    Pickable::IGNORE,
+                ),
+            ]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spawners_path_to_nearest_tower() {
+        let towers = [Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)];
+        let spawners = [Vec2::new(-300.0, 50.0), Vec2::new(250.0, -50.0)];
+        assert_eq!(
+            paths(&spawners, &towers).collect::<Vec<_>>(),
+            vec![(spawners[0], towers[0]), (spawners[1], towers[1])]
+        );
+        assert_eq!(paths(&spawners, &[]).count(), 0);
+    }
+}
@@ -0,0 +1,97 @@
+//! Poisson disk sampling (Bridson's algorithm).
+
+use bevy::prelude::*;
+use rand::Rng;
+use std::f32::consts::TAU;
+
+/// Candidates tried around each active sample before it's retired.
+const ATTEMPTS: usize = 30;
+
+/// Points inside a `size` rectangle centered on the origin that are at least `min_distance` apart.
+pub fn poisson_disk<R: Rng>(rng: &mut R, size: Vec2, min_distance: f32) -> Vec<Vec2> {
+    if min_distance <= 0.0 || size.x <= 0.0 || size.y <= 0.0 {
+        return Vec::new();
+    }
+    // Each cell can hold at most one sample.
+    let cell_size = min_distance / std::f32::consts::SQRT_2;
+    let columns = (size.x / cell_size).ceil() as usize;
+    let rows = (size.y / cell_size).ceil() as usize;
+    let mut grid: Vec<Option<usize>> = vec![None; columns * rows];
+    let cell_of = |p: Vec2| {
+        let column = ((p.x / cell_size) as usize).min(columns - 1);
+        let row = ((p.y / cell_size) as usize).min(rows - 1);
+        (column, row)
+    };
+
+    let mut samples = Vec::new();
+    let mut active = Vec::new();
+    let first = Vec2::new(rng.random_range(0.0..size.x), rng.random_range(0.0..size.y));
+    let (column, row) = cell_of(first);
+    grid[row * columns + column] = Some(0);
+    samples.push(first);
+    active.push(0);
+
+    while !active.is_empty() {
+        let active_ix = rng.random_range(0..active.len());
+        let origin = samples[active[active_ix]];
+        let mut found = false;
+        for _ in 0..ATTEMPTS {
+            let angle = rng.random_range(0.0..TAU);
+            let distance = rng.random_range(min_distance..2.0 * min_distance);
+            let candidate = origin + Vec2::from_angle(angle) * distance;
+            if candidate.x < 0.0
+                || candidate.y < 0.0
+                || candidate.x >= size.x
+                || candidate.y >= size.y
+            {
+                continue;
+            }
+            let (column, row) = cell_of(candidate);
+            let too_close = (row.saturating_sub(2)..(row + 3).min(rows)).any(|r| {
+                (column.saturating_sub(2)..(column + 3).min(columns)).any(|c| {
+                    grid[r * columns + c].is_some_and(|ix| {
+                        samples[ix].distance_squared(candidate) < min_distance * min_distance
+                    })
+                })
+            });
+            if too_close {
+                continue;
+            }
+            grid[row * columns + column] = Some(samples.len());
+            active.push(samples.len());
+            samples.push(candidate);
+            found = true;
+            break;
+        }
+        if !found {
+            active.swap_remove(active_ix);
+        }
+    }
+
+    samples.into_iter().map(|p| p - size / 2.0).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::Prng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn samples_keep_their_distance() {
+        let size = Vec2::new(400.0, 300.0);
+        let min_distance = 25.0;
+        let samples = poisson_disk(&mut Prng::seed_from_u64(5), size, min_distance);
+        // Bridson's algorithm fills the area densely.
+        assert!(samples.len() > 50);
+        for (ix, a) in samples.iter().enumerate() {
+            assert!((a.abs() * 2.0).cmple(size).all(), "{a} outside the area");
+            for b in &samples[ix + 1..] {
+                assert!(
+                    a.distance(*b) >= min_distance - 1e-3,
+                    "{a} and {b} too close"
+                );
+            }
+        }
+    }
+}
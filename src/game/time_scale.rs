@@ -0,0 +1,188 @@
+//! Slow motion and fast forward.
+//!
+//! [`TimeScale`] sets the speed of virtual time. Virtual time drives the fixed gameplay
+//! timestep and physics runs on that, so physics follows without a speed of its own. Killing
+//! several enemies in quick succession briefly adds bullet time on top.
+
+use crate::game::health::Dead;
+use crate::game::pause_controller::UiSystems;
+use crate::game::prefabs::enemy::Enemy;
+use crate::game::screens::Screen;
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+use bevy_auto_plugin::auto_plugin::*;
+use smart_default::SmartDefault;
+
+/// Speeds the player can pick from.
+pub const TIME_SCALE_STEPS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct TimeScale {
+    /// Index into [`TIME_SCALE_STEPS`].
+    #[default(2)]
+    pub step: usize,
+}
+
+impl TimeScale {
+    pub fn scale(&self) -> f32 {
+        TIME_SCALE_STEPS[self.step.min(TIME_SCALE_STEPS.len() - 1)]
+    }
+
+    pub fn faster(&mut self) {
+        self.step = (self.step + 1).min(TIME_SCALE_STEPS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, SmartDefault, Copy, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct BulletTimeConfig {
+    /// Kills within `window_secs` of each other that trigger bullet time.
+    #[default(3)]
+    pub kills: usize,
+    #[default(0.3)]
+    pub window_secs: f32,
+    /// Speed during bullet time, relative to the [`TimeScale`].
+    #[default(0.3)]
+    pub scale: f32,
+    /// Real seconds bullet time lasts, easing back to full speed on the way.
+    #[default(1.2)]
+    pub duration_secs: f32,
+}
+
+#[auto_register_type]
+#[auto_init_resource]
+#[derive(Resource, Debug, Default, Clone, Reflect)]
+#[reflect(Resource)]
+struct BulletTime {
+    /// Real time of the latest kills.
+    recent_kills: Vec<f32>,
+    timer: Option<Timer>,
+}
+
+impl BulletTime {
+    fn factor(&self, config: &BulletTimeConfig) -> f32 {
+        let Some(timer) = self.timer.as_ref() else {
+            return 1.0;
+        };
+        let t = timer.fraction();
+        config.scale + (1.0 - config.scale) * t * t
+    }
+}
+
+#[auto_plugin(app=app)]
+pub(crate) fn plugin(app: &mut App) {
+    app.add_observer(on_enemy_killed);
+    app.add_systems(
+        Update,
+        (
+            (|mut scale: ResMut<TimeScale>| scale.slower())
+                .run_if(input_just_pressed(KeyCode::BracketLeft)),
+            (|mut scale: ResMut<TimeScale>| scale.faster())
+                .run_if(input_just_pressed(KeyCode::BracketRight)),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(UiSystems),
+    );
+    // Before the fixed loop, which is what the speed is for.
+    app.add_systems(PreUpdate, apply_time_scale);
+    app.add_systems(OnExit(Screen::Gameplay), reset);
+}
+
+fn on_enemy_killed(
+    trigger: Trigger<OnAdd, Dead>,
+    real_time: Res<Time<Real>>,
+    config: Res<BulletTimeConfig>,
+    mut bullet_time: ResMut<BulletTime>,
+    enemies: Query<(), With<Enemy>>,
+) {
+    if !enemies.contains(trigger.target()) {
+        return;
+    }
+    let now = real_time.elapsed_secs();
+    bullet_time
+        .recent_kills
+        .retain(|&at| now - at <= config.window_secs);
+    bullet_time.recent_kills.push(now);
+    if bullet_time.recent_kills.len() >= config.kills {
+        bullet_time.recent_kills.clear();
+        bullet_time.timer = Some(Timer::from_seconds(config.duration_secs, TimerMode::Once));
+    }
+}
+
+fn apply_time_scale(
+    real_time: Res<Time<Real>>,
+    scale: Res<TimeScale>,
+    config: Res<BulletTimeConfig>,
+    mut bullet_time: ResMut<BulletTime>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    // Bullet time is measured in real time, otherwise it would slow itself down.
+    if let Some(timer) = bullet_time.timer.as_mut() {
+        if timer.tick(real_time.delta()).finished() {
+            bullet_time.timer = None;
+        }
+    }
+    let speed = scale.scale() * bullet_time.factor(&config);
+    if virtual_time.relative_speed() != speed {
+        virtual_time.set_relative_speed(speed);
+    }
+}
+
+fn reset(mut scale: ResMut<TimeScale>, mut bullet_time: ResMut<BulletTime>) {
+    *scale = TimeScale::default();
+    *bullet_time = BulletTime::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn time_scale_steps_clamp() {
+        let mut scale = TimeScale::default();
+        assert_eq!(scale.scale(), 1.0);
+        scale.faster();
+        assert_eq!(scale.scale(), 2.0);
+        for _ in 0..TIME_SCALE_STEPS.len() {
+            scale.faster();
+        }
+        assert_eq!(scale.scale(), 4.0);
+        for _ in 0..TIME_SCALE_STEPS.len() {
+            scale.slower();
+        }
+        assert_eq!(scale.scale(), 0.25);
+    }
+
+    #[test]
+    fn bullet_time_eases_back() {
+        let config = BulletTimeConfig::default();
+        let mut bullet_time = BulletTime::default();
+        assert_eq!(bullet_time.factor(&config), 1.0);
+
+        bullet_time.timer = Some(Timer::from_seconds(1.0, TimerMode::Once));
+        assert_eq!(bullet_time.factor(&config), config.scale);
+        bullet_time
+            .timer
+            .as_mut()
+            .unwrap()
+            .tick(Duration::from_secs_f32(0.5));
+        let halfway = bullet_time.factor(&config);
+        assert!((halfway - (config.scale + (1.0 - config.scale) * 0.25)).abs() < 1e-6);
+        bullet_time
+            .timer
+            .as_mut()
+            .unwrap()
+            .tick(Duration::from_secs(1));
+        assert_eq!(bullet_time.factor(&config), 1.0);
+    }
+}